@@ -1,19 +1,40 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use crate::application::controls::Control;
+use crate::knowledge_base::{matches_pattern, KnowledgeBase};
+use crate::profile;
+use crate::profile::{Profile, ProfileEntry};
 use crate::sysfs_firmware_attributes::{
-    autodetect_root, Attribute, AttributeError, AttributeParser, Mechanism,
+    autodetect_root, container_environment, is_firmware_attributes_root,
+    kernel_compatibility_warning, read_attributes_with_progress, Attribute, AttributeError,
+    AttributeParser, AttributeValue, Authentication, LoadedAttributes, Mechanism, Role,
+    PATH_SYSFS_FIRMWARE_ATTRIBUTES,
 };
 use chrono::{DateTime, Local};
 use eframe::glow::Context;
 use egui::{Key, RichText};
-use log::{error, info};
+use log::{error, info, warn};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::error::Error;
+use std::fs;
+use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
 mod controls;
 
+const STORAGE_KEY_LAST_MODIFIED: &str = "last_modified";
+const STORAGE_KEY_LAST_ROOT: &str = "last_root";
+const STORAGE_KEY_THEME: &str = "theme";
+const STORAGE_KEY_RECOVERY: &str = "recovery";
+const RECOVERY_SAVE_INTERVAL: Duration = Duration::from_secs(5);
+const REBOOT_COMMAND: &str = "systemctl reboot";
+const UNCATEGORIZED: &str = "Other";
+const REBOOT_RETRY_DELAY: Duration = Duration::from_millis(250);
+const POWER_COUNTDOWN: Duration = Duration::from_secs(5);
+
 pub enum Application<T: AttributeParser> {
     SelectRoot {
         roots: Vec<PathBuf>,
@@ -22,13 +43,31 @@ pub enum Application<T: AttributeParser> {
     BiosAdminAuthentication {
         root: PathBuf,
         authentication: T::Auth,
+        /// Password, or the path to the signature file for the certificate mechanism
         password: String,
+        /// Failed logins in this session, counted against the firmware retry limit
+        failed_attempts: u32,
+        /// All enabled authentications, the user picks the role when there are several
+        enabled: Vec<T::Auth>,
         status: Status,
     },
     BiosAttributes {
         root: PathBuf,
         access_mode: AccessMode<T>,
         controls: Vec<Control<T>>,
+        /// Names and parse errors of the skipped Attributes
+        unsupported: Vec<(String, String)>,
+        /// Attributes read by the worker thread, the controls are empty until it finishes
+        loading: Option<Arc<Mutex<Loading>>>,
+        authentications: Vec<T::Auth>,
+        authentication_toggle: Option<usize>,
+        details: Option<String>,
+        filter: String,
+        filter_possible_values: bool,
+        sort: AttributeSort,
+        prefix_groups: bool,
+        /// Only the Attributes whose current value differs from the default are shown
+        modified_only: bool,
         status: Status,
     },
 }
@@ -46,17 +85,431 @@ impl<T: AttributeParser> Application<T> {
         status.inner.lock().unwrap().reboot_required = matches!(T::pending_reboot(root), Ok(true));
     }
 
-    pub fn autodetect_root() -> Self {
+    /// Applies the theme selected on the command line or in the previous session
+    pub fn apply_theme(&self, ctx: &egui::Context) {
+        if let Some(dark_mode) = self.status().dark_mode() {
+            ctx.set_visuals(visuals(dark_mode));
+        }
+    }
+
+    pub fn autodetect_root(status: &Status) -> Self {
         let roots = autodetect_root();
-        Self::select_root(roots)
+        Self::select_root(roots, status)
     }
 
-    pub fn select_root(roots: Vec<PathBuf>) -> Self {
+    pub fn select_root(roots: Vec<PathBuf>, status: &Status) -> Self {
         Self::SelectRoot {
             roots,
-            status: Status::default(),
+            status: status.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AttributeSort {
+    /// Order reported by the firmware
+    #[default]
+    Firmware,
+    Name,
+    DisplayName,
+    /// Groups like controls together, by display name inside of the group
+    Type,
+}
+
+impl AttributeSort {
+    const ALL: [AttributeSort; 4] = [
+        AttributeSort::Firmware,
+        AttributeSort::Name,
+        AttributeSort::DisplayName,
+        AttributeSort::Type,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            AttributeSort::Firmware => "Firmware order",
+            AttributeSort::Name => "By name",
+            AttributeSort::DisplayName => "By display name",
+            AttributeSort::Type => "By type",
+        }
+    }
+
+    fn sort(&self, controls: &mut [&Control<Attribute>]) {
+        match self {
+            AttributeSort::Firmware => {}
+            AttributeSort::Name => {
+                controls.sort_by(|a, b| a.attribute().name().cmp(b.attribute().name()))
+            }
+            AttributeSort::DisplayName => controls.sort_by(|a, b| {
+                a.attribute()
+                    .display_name()
+                    .to_lowercase()
+                    .cmp(&b.attribute().display_name().to_lowercase())
+            }),
+            AttributeSort::Type => controls.sort_by_cached_key(|control| {
+                let attribute = control.attribute();
+                (
+                    type_rank(attribute),
+                    attribute.display_name().to_lowercase(),
+                )
+            }),
+        }
+    }
+}
+
+fn type_rank(attribute: &Attribute) -> u8 {
+    match attribute {
+        Attribute::Enumeration(_) => 0,
+        Attribute::Integer(_) => 1,
+        Attribute::String(_) => 2,
+        Attribute::OrderedList(_) => 3,
+        Attribute::EnumerationList(_) => 4,
+        Attribute::Map(_) => 5,
+        Attribute::Raw(_) => 6,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Theme {
+    Dark,
+    Light,
+    /// Follows the system preference
+    Auto,
+}
+
+impl Theme {
+    fn dark_mode(self) -> Option<bool> {
+        match self {
+            Theme::Dark => Some(true),
+            Theme::Light => Some(false),
+            Theme::Auto => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// Attributes names patterns, `*` matches any sequence of characters
+    pub size_patterns: Vec<String>,
+    /// Additional reboot attempts, the delay doubles after each one
+    pub reboot_retries: u32,
+    /// Show manual reboot instructions when all attempts failed
+    pub reboot_fallback: bool,
+    /// Directory for the profile exported before the first write, `None` disables snapshots
+    pub snapshot_directory: Option<PathBuf>,
+    /// Show the panel with internal state for debugging
+    pub debug_ui: bool,
+    pub knowledge_base: KnowledgeBase,
+    /// Values are re-read after a gap between frames longer than this, for example after sleep
+    pub refresh_after: Duration,
+    /// Interval of the comparison of cached values with the firmware, disabled when `None`
+    pub poll_interval: Option<Duration>,
+    /// Separator of the name prefix used to group Attributes, camelCase boundaries when `None`
+    pub group_separator: Option<char>,
+    /// Allow adding the same entry to ordered lists several times, some firmware accepts it
+    pub ordered_list_duplicates: bool,
+    /// Store the selected root and open it on the next launch
+    pub remember_root: bool,
+    /// Number of writes that can be undone
+    pub undo_depth: usize,
+    /// Cached current values older than this are read again, kept until a write when `None`
+    pub cache_ttl: Option<Duration>,
+    /// Theme requested on the command line, the toggled theme of the previous session when `None`
+    pub theme: Option<Theme>,
+    /// Open roots read-only without the login, for demos and audits
+    pub read_only: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            size_patterns: Vec::new(),
+            reboot_retries: 2,
+            reboot_fallback: true,
+            snapshot_directory: Some(default_snapshot_directory()),
+            debug_ui: false,
+            knowledge_base: KnowledgeBase::bundled(),
+            refresh_after: Duration::from_secs(60),
+            poll_interval: Some(Duration::from_secs(10)),
+            group_separator: None,
+            ordered_list_duplicates: true,
+            remember_root: true,
+            undo_depth: 20,
+            cache_ttl: None,
+            theme: None,
+            read_only: false,
+        }
+    }
+}
+
+// Command line options of the GUI, the subcommands don't use them.
+// Not a doc comment, clap would show it instead of the application description
+#[derive(clap::Args)]
+pub struct GuiArgs {
+    /// Require a confirmation for changes of Attributes matching the pattern, in addition to
+    /// the ones with known consequences. Can be specified multiple times or with
+    /// FW_ATTR_EDITOR_SENSITIVE env variable, comma separated
+    #[arg(long = "sensitive", value_name = "PATTERN")]
+    sensitive_patterns: Vec<String>,
+
+    /// Show Integer Attributes matching the pattern as byte sizes, for example "*MemorySize*".
+    /// Can be specified multiple times
+    #[arg(long = "size", value_name = "PATTERN")]
+    size_patterns: Vec<String>,
+
+    /// Number of additional reboot attempts when the reboot request fails
+    #[arg(long, default_value_t = 2)]
+    reboot_retries: u32,
+
+    /// Do not show manual reboot instructions when the reboot request fails
+    #[arg(long)]
+    no_reboot_fallback: bool,
+
+    /// Do not export all attributes before the first change in the session
+    #[arg(long)]
+    no_snapshot: bool,
+
+    /// Directory for the snapshots. Default: $XDG_STATE_HOME/fw-attr-editor/snapshots
+    #[arg(long)]
+    snapshot_dir: Option<PathBuf>,
+
+    /// Show the debug panel with the state of the current values cache
+    #[arg(long)]
+    debug_ui: bool,
+
+    /// Re-read values when the window regains focus or after a gap between frames
+    /// longer than this number of seconds, for example after sleep
+    #[arg(long, default_value_t = 60)]
+    refresh_after_secs: u64,
+
+    /// Compare cached values with the firmware every number of seconds and highlight the ones
+    /// changed outside of the Editor, 0 disables it
+    #[arg(long, default_value_t = 10)]
+    poll_secs: u64,
+
+    /// Character separating the group prefix in Attribute names, camelCase boundaries are used
+    /// when absent
+    #[arg(long, value_name = "CHAR")]
+    group_separator: Option<char>,
+
+    /// Don't offer entries already present in ordered lists, enumeration lists never allow duplicates
+    #[arg(long)]
+    no_ordered_list_duplicates: bool,
+
+    /// Read current values again when the cached value is older than this number of milliseconds.
+    /// Values are cached until a write by default
+    #[arg(long, value_name = "MS")]
+    cache_ttl_ms: Option<u64>,
+
+    /// Number of writes that can be undone
+    #[arg(long, default_value_t = 20)]
+    undo_depth: usize,
+
+    /// Do not store the selected root and open it on the next launch
+    #[arg(long)]
+    no_remember: bool,
+
+    /// Color theme, the theme toggled in the previous session is used when absent
+    #[arg(long, value_enum)]
+    pub theme: Option<Theme>,
+
+    /// Do not restore the window size and position of the previous session
+    #[arg(long)]
+    pub no_persist_window: bool,
+}
+
+impl Settings {
+    pub fn from_args(args: GuiArgs, mut knowledge_base: KnowledgeBase, read_only: bool) -> Self {
+        knowledge_base.add_sensitive_from_env();
+        for pattern in &args.sensitive_patterns {
+            knowledge_base.add_sensitive(pattern);
+        }
+        Self {
+            size_patterns: args.size_patterns,
+            reboot_retries: args.reboot_retries,
+            reboot_fallback: !args.no_reboot_fallback,
+            snapshot_directory: if args.no_snapshot {
+                None
+            } else {
+                Some(args.snapshot_dir.unwrap_or_else(default_snapshot_directory))
+            },
+            debug_ui: args.debug_ui,
+            knowledge_base,
+            refresh_after: Duration::from_secs(args.refresh_after_secs),
+            poll_interval: Some(Duration::from_secs(args.poll_secs))
+                .filter(|interval| !interval.is_zero()),
+            group_separator: args.group_separator,
+            ordered_list_duplicates: !args.no_ordered_list_duplicates,
+            remember_root: !args.no_remember,
+            undo_depth: args.undo_depth,
+            cache_ttl: args.cache_ttl_ms.map(Duration::from_millis),
+            theme: args.theme,
+            read_only,
+        }
+    }
+
+    fn is_size(&self, attribute_name: &str) -> bool {
+        self.size_patterns
+            .iter()
+            .any(|pattern| matches_pattern(pattern, attribute_name))
+    }
+}
+
+/// Attributes without a default value are never modified
+fn is_modified(attribute: &Attribute) -> bool {
+    match (attribute.default_value(), attribute.current_value()) {
+        (Some(default_value), Ok(current_value)) => default_value != current_value,
+        _ => false,
+    }
+}
+
+/// Case-insensitive substring match against the names and optionally the possible values
+fn matches_filter(attribute: &Attribute, filter: &str, possible_values: bool) -> bool {
+    let filter = filter.to_lowercase();
+    let matches = |value: &String| value.to_lowercase().contains(&filter);
+    if matches(attribute.name()) || matches(attribute.display_name()) {
+        return true;
+    }
+    if !possible_values {
+        return false;
+    }
+    match attribute {
+        Attribute::Enumeration(attr) => attr.possible_values().iter().any(matches),
+        Attribute::EnumerationList(attr) => attr.possible_values.iter().any(matches),
+        _ => false,
+    }
+}
+
+/// Groups in the order of the first appearance, uncategorized Attributes go last
+fn group_by_category<'a>(
+    controls: &[&'a Control<Attribute>],
+) -> Vec<(&'a str, Vec<&'a Control<Attribute>>)> {
+    let mut groups: Vec<(&str, Vec<&Control<Attribute>>)> = Vec::new();
+    for &control in controls {
+        let category = control
+            .attribute()
+            .category()
+            .map_or(UNCATEGORIZED, String::as_str);
+        match groups.iter_mut().find(|(name, _)| *name == category) {
+            Some((_, group)) => group.push(control),
+            None => groups.push((category, vec![control])),
+        }
+    }
+    groups.sort_by_key(|(name, _)| *name == UNCATEGORIZED);
+    groups
+}
+
+/// Leading word of the name: up to the separator or the first camelCase boundary,
+/// acronyms like "USBPort" are split before the last capital letter
+fn name_prefix(name: &str, separator: Option<char>) -> &str {
+    if let Some(separator) = separator {
+        return name.split(separator).next().unwrap_or(name);
+    }
+    let chars: Vec<(usize, char)> = name.char_indices().collect();
+    for i in 1..chars.len() {
+        let (index, c) = chars[i];
+        if !c.is_uppercase() {
+            continue;
+        }
+        let previous = chars[i - 1].1;
+        let next_lowercase = chars.get(i + 1).is_some_and(|(_, c)| c.is_lowercase());
+        if previous.is_lowercase()
+            || previous.is_ascii_digit()
+            || (previous.is_uppercase() && next_lowercase && i > 1)
+        {
+            return &name[..index];
+        }
+    }
+    name
+}
+
+/// Groups in the order of the first appearance, prefixes with a single Attribute go last
+fn group_by_prefix<'a>(
+    controls: &[&'a Control<Attribute>],
+    separator: Option<char>,
+) -> Vec<(&'a str, Vec<&'a Control<Attribute>>)> {
+    let mut groups: Vec<(&str, Vec<&Control<Attribute>>)> = Vec::new();
+    for &control in controls {
+        let prefix = name_prefix(control.attribute().name(), separator);
+        match groups.iter_mut().find(|(name, _)| *name == prefix) {
+            Some((_, group)) => group.push(control),
+            None => groups.push((prefix, vec![control])),
         }
     }
+    let (mut groups, singles): (Vec<_>, Vec<_>) =
+        groups.into_iter().partition(|(_, group)| group.len() > 1);
+    if !singles.is_empty() {
+        groups.push((
+            UNCATEGORIZED,
+            singles.into_iter().flat_map(|(_, group)| group).collect(),
+        ));
+    }
+    groups
+}
+
+/// Keyboard shortcuts of the attributes form
+struct Shortcuts {
+    /// `/` while no widget has the focus
+    focus_search: bool,
+    /// `Ctrl+S`, opens the "Reset all to defaults" confirmation
+    reset_all: bool,
+    /// `Ctrl+R`
+    refresh: bool,
+    /// `Esc`
+    clear_filter: bool,
+}
+
+impl Shortcuts {
+    fn read(ui: &egui::Ui) -> Self {
+        let unfocused = ui.memory(|mem| mem.focus().is_none());
+        ui.ctx().input_mut(|i| Self {
+            focus_search: unfocused
+                && i.events
+                    .iter()
+                    .any(|event| matches!(event, egui::Event::Text(text) if text == "/")),
+            reset_all: i.consume_key(egui::Modifiers::COMMAND, Key::S),
+            refresh: i.consume_key(egui::Modifiers::COMMAND, Key::R),
+            clear_filter: i.key_pressed(Key::Escape),
+        })
+    }
+}
+
+fn visuals(dark_mode: bool) -> egui::Visuals {
+    if dark_mode {
+        egui::Visuals::dark()
+    } else {
+        egui::Visuals::light()
+    }
+}
+
+fn state_directory() -> PathBuf {
+    std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))
+        .unwrap_or_else(std::env::temp_dir)
+        .join("fw-attr-editor")
+}
+
+pub fn default_snapshot_directory() -> PathBuf {
+    state_directory().join("snapshots")
+}
+
+fn profiles_directory() -> PathBuf {
+    state_directory().join("profiles")
+}
+
+fn default_export_path() -> PathBuf {
+    profiles_directory().join(format!(
+        "profile-{}.json",
+        Local::now().format("%Y%m%d-%H%M%S")
+    ))
+}
+
+fn recovery_file_path() -> PathBuf {
+    state_directory().join("recovery.json")
+}
+
+fn raw_export_directory() -> PathBuf {
+    state_directory().join("raw")
 }
 
 #[derive(Clone, Debug)]
@@ -65,6 +518,50 @@ pub struct Status {
 }
 
 impl Status {
+    pub fn new(settings: Settings) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(StatusInner {
+                changed: Local::now(),
+                message: StatusMessage::Ok,
+                reboot_required: false,
+                session_changes: Vec::new(),
+                undo_stack: Vec::new(),
+                apply_and_reboot_confirmation: false,
+                reset_all_confirmation: false,
+                staging: false,
+                staged: BTreeMap::new(),
+                apply_staged_confirmation: false,
+                recovery_path: None,
+                recovery_dirty: false,
+                last_recovery_save: Instant::now(),
+                recovery_offer: None,
+                export_path: None,
+                import_path: None,
+                import_warnings: None,
+                password_change: None,
+                power_confirmation: None,
+                power_deadline: None,
+                reboot_fallback: false,
+                write_failure: None,
+                snapshot: None,
+                kernel_warning: None,
+                pending_write: None,
+                last_update: Instant::now(),
+                last_poll: Instant::now(),
+                external_changes: HashMap::new(),
+                focused: true,
+                view_only: false,
+                display_language: None,
+                last_root: None,
+                dark_mode: settings.theme.and_then(Theme::dark_mode),
+                types_check: false,
+                refresh_requested: false,
+                last_modified: HashMap::new(),
+                settings,
+            })),
+        }
+    }
+
     fn inner(&self) -> StatusInner {
         self.inner.lock().unwrap().clone()
     }
@@ -109,131 +606,1032 @@ impl Status {
     }
 }
 
-impl Default for Status {
-    fn default() -> Self {
-        Self {
-            inner: Arc::new(Mutex::new(StatusInner {
-                changed: Local::now(),
-                message: StatusMessage::Ok,
-                reboot_required: false,
-            })),
-        }
+impl Status {
+    fn show_message(&self, message: String) {
+        info!("{}", message);
+        let mut inner = self.inner.lock().unwrap();
+        inner.changed = Local::now();
+        inner.message = StatusMessage::Message(message);
     }
-}
 
-#[derive(Debug, Clone)]
-struct StatusInner {
-    changed: DateTime<Local>,
-    message: StatusMessage,
-    reboot_required: bool,
-}
+    fn has_error(&self) -> bool {
+        matches!(self.inner.lock().unwrap().message, StatusMessage::Error(_))
+    }
 
-#[derive(Clone, Debug)]
-enum StatusMessage {
-    Ok,
-    Message(String),
-    Error(String),
-}
+    fn show_error(&self, message: String) {
+        error!("{}", message);
+        let mut inner = self.inner.lock().unwrap();
+        inner.changed = Local::now();
+        inner.message = StatusMessage::Error(message);
+    }
 
-pub enum AccessMode<T: AttributeParser> {
-    ReadOnly,
-    ReadWrite,
-    ReadWriteAuthenticated(T::Auth),
-}
+    /// Keeps the last `undo_depth` previous values
+    fn record_undo(&self, name: &str, previous_value: AttributeValue) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.undo_stack.push((name.to_string(), previous_value));
+        let excess = inner
+            .undo_stack
+            .len()
+            .saturating_sub(inner.settings.undo_depth);
+        inner.undo_stack.drain(..excess);
+    }
 
-impl<T: AttributeParser> AccessMode<T> {
-    pub fn write_access(&self) -> bool {
-        match self {
-            AccessMode::ReadOnly => false,
-            _ => true,
-        }
+    fn last_undo(&self) -> Option<(String, AttributeValue)> {
+        self.inner.lock().unwrap().undo_stack.last().cloned()
     }
-}
 
-impl eframe::App for Application<Attribute> {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::TopBottomPanel::top("Header").show(ctx, |ui| {
-            self.header_bar(ui);
-        });
-        egui::TopBottomPanel::bottom("Status").show(ctx, |ui| {
-            self.status_bar(ui);
-        });
-        egui::CentralPanel::default().show(ctx, |ui| match self {
-            Application::BiosAdminAuthentication { .. } => {
-                self.bios_admin_authentication_ui(ui);
-            }
-            Application::BiosAttributes { .. } => {
-                self.attributes_edit_form(ui);
-            }
-            Application::SelectRoot { roots, status } => {
-                if roots.len() == 1 {
-                    let root = roots.first().unwrap();
-                    if let Some(state) = status.handle_result_with_message(
-                        Self::bios_admin_authentication(root, status),
-                        &format!("The Only Root {:?} was selected automatically", root),
-                    ) {
-                        *self = state;
-                        ctx.request_repaint();
-                    }
-                } else {
-                    self.select_root_ui(ui);
-                }
-            }
-        });
+    /// Removes the last entry, returns the number of the remaining ones
+    fn pop_undo(&self) -> usize {
+        let mut inner = self.inner.lock().unwrap();
+        inner.undo_stack.pop();
+        inner.undo_stack.len()
     }
 
-    fn on_exit(&mut self, _gl: Option<&Context>) {
-        if let Self::BiosAttributes {
-            access_mode: AccessMode::ReadWriteAuthenticated(auth),
-            ..
-        } = self
-        {
-            // Logout
-            let _ = auth.authenticate_with_password("");
-        }
+    fn truncate_undo(&self, len: usize) {
+        self.inner.lock().unwrap().undo_stack.truncate(len);
     }
-}
 
-impl Application<Attribute> {
-    pub fn bios_attributes(
-        path: &Path,
-        access_mode: AccessMode<Attribute>,
-        status: &Status,
-    ) -> Result<Self, AttributeError> {
-        let attributes_names = Attribute::attributes_names(path).unwrap();
-        let controls: Vec<Control<Attribute>> = attributes_names
-            .iter()
-            .filter_map(|name| Attribute::attribute(path, name).ok())
-            .map(|attribute| Control::new(attribute, status))
-            .collect();
-        Self::check_pending_reboot(path, &status);
-        Ok(Self::BiosAttributes {
-            root: path.to_path_buf(),
-            access_mode,
-            controls,
-            status: status.clone(),
-        })
+    fn record_change(&self, change: String) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.session_changes.push(change);
+        // Some firmware changes the schema of dependent Attributes
+        inner.types_check = true;
     }
 
-    pub fn bios_admin_authentication(path: &Path, status: &Status) -> Result<Self, AttributeError> {
-        let authentication_names = Attribute::authentications_names(path)?;
-        for name in authentication_names {
-            let authentication = Attribute::authentication(path, &name)?;
-            if authentication.is_enabled && matches!(authentication.mechanism, Mechanism::Password)
-            {
-                return Ok(Self::BiosAdminAuthentication {
-                    root: path.to_path_buf(),
-                    authentication,
-                    password: String::new(),
-                    status: status.clone(),
-                });
-            }
+    fn take_types_check(&self) -> bool {
+        std::mem::take(&mut self.inner.lock().unwrap().types_check)
+    }
+
+    fn set_apply_and_reboot_confirmation(&self, visible: bool) {
+        self.inner.lock().unwrap().apply_and_reboot_confirmation = visible;
+    }
+
+    fn reset_all_confirmation(&self) -> bool {
+        self.inner.lock().unwrap().reset_all_confirmation
+    }
+
+    fn set_reset_all_confirmation(&self, visible: bool) {
+        self.inner.lock().unwrap().reset_all_confirmation = visible;
+    }
+
+    fn export_path(&self) -> Option<String> {
+        self.inner.lock().unwrap().export_path.clone()
+    }
+
+    fn set_export_path(&self, path: Option<String>) {
+        self.inner.lock().unwrap().export_path = path;
+    }
+
+    fn password_change(&self) -> Option<PasswordChange> {
+        self.inner.lock().unwrap().password_change.clone()
+    }
+
+    fn set_password_change(&self, password_change: Option<PasswordChange>) {
+        self.inner.lock().unwrap().password_change = password_change;
+    }
+
+    fn import_path(&self) -> Option<String> {
+        self.inner.lock().unwrap().import_path.clone()
+    }
+
+    /// Warnings shown for the previous path don't apply to the new one
+    fn set_import_path(&self, path: Option<String>) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.import_path = path;
+        inner.import_warnings = None;
+    }
+
+    fn import_warnings(&self) -> Option<Vec<String>> {
+        self.inner.lock().unwrap().import_warnings.clone()
+    }
+
+    fn set_import_warnings(&self, warnings: Vec<String>) {
+        self.inner.lock().unwrap().import_warnings = Some(warnings);
+    }
+
+    /// Mismatches of the machine recorded in the profile, `None` when the profile cannot be read
+    fn profile_warnings(&self, root: &Path, path: &Path) -> Option<Vec<String>> {
+        let profile = self.handle_result(Profile::from_file(path))?;
+        Some(profile.warnings(root))
+    }
+
+    /// Writes the profile values and reports the skipped and failed Attributes,
+    /// the warnings are the ones already shown for the profile
+    fn import_json(&self, root: &Path, path: &Path, warnings: &[String]) {
+        let knowledge_base = self.inner.lock().unwrap().settings.knowledge_base.clone();
+        self.take_snapshot(root);
+        let result = profile::import_json(root, path, warnings, &knowledge_base);
+        let Some(report) = self.handle_result(result) else {
+            return;
+        };
+        info!("Import of {:?}:\n{}", path, report);
+        for warning in &report.warnings {
+            warn!("{}", warning);
+        }
+        for name in &report.changed {
+            self.record_change(format!("{}: imported from {:?}", name, path));
+        }
+        let mut message = format!(
+            "Imported {:?}: {} changed, {} unchanged",
+            path,
+            report.changed.len(),
+            report.unchanged.len()
+        );
+        if !report.skipped.is_empty() {
+            message.push_str(&format!(
+                ", skipped as not found: {}",
+                report.skipped.join(", ")
+            ));
         }
-        Self::bios_attributes(path, AccessMode::ReadWrite, status)
+        if !report.warnings.is_empty() {
+            message.push_str(&format!(", warnings: {}", report.warnings.join("; ")));
+        }
+        if report.failed.is_empty() {
+            self.show_message(message);
+        } else {
+            let failed: Vec<String> = report
+                .failed
+                .iter()
+                .map(|(name, err)| format!("{}: {}", name, err))
+                .collect();
+            message.push_str(&format!(", failed: {}", failed.join("; ")));
+            self.handle_result::<()>(Err(AttributeError::InvalidValue(message)));
+        }
+        // Controls hold cached values
+        self.inner.lock().unwrap().refresh_requested = true;
     }
 
-    fn bios_admin_authentication_ui(&mut self, ui: &mut egui::Ui) {
-        ui.vertical_centered(|ui| {
+    fn set_power_confirmation(&self, action: Option<PowerAction>) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.power_confirmation = action;
+        inner.power_deadline = None;
+    }
+
+    fn start_power_countdown(&self) {
+        self.inner.lock().unwrap().power_deadline = Some(Instant::now() + POWER_COUNTDOWN);
+    }
+
+    fn shutdown(&self) {
+        self.handle_result_with_message(system_shutdown::shutdown(), "Shutting down...");
+    }
+
+    fn set_reboot_fallback(&self, visible: bool) {
+        self.inner.lock().unwrap().reboot_fallback = visible;
+    }
+
+    fn reboot(&self) {
+        let settings = self.inner.lock().unwrap().settings.clone();
+        let mut delay = REBOOT_RETRY_DELAY;
+        let mut result = system_shutdown::reboot();
+        for attempt in 1..=settings.reboot_retries {
+            let Err(err) = &result else {
+                break;
+            };
+            warn!("Reboot attempt {} failed: {:?}", attempt, err);
+            thread::sleep(delay);
+            delay *= 2;
+            result = system_shutdown::reboot();
+        }
+        if self
+            .handle_result_with_message(result, "Rebooting...")
+            .is_none()
+            && settings.reboot_fallback
+        {
+            self.set_reboot_fallback(true);
+        }
+    }
+
+    fn record_write_failure(&self, name: &str, err: &AttributeError) {
+        if let AttributeError::WriteRejected { errno, .. } = err.root_cause() {
+            self.inner.lock().unwrap().write_failure = Some((name.to_string(), *errno));
+        }
+    }
+
+    fn write_failure(&self) -> Option<(String, Option<i32>)> {
+        self.inner.lock().unwrap().write_failure.clone()
+    }
+
+    fn clear_write_failure(&self) {
+        self.inner.lock().unwrap().write_failure = None;
+    }
+
+    fn take_snapshot(&self, root: &Path) {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(directory) = &inner.settings.snapshot_directory else {
+            return;
+        };
+        if inner.snapshot.is_some() {
+            return;
+        }
+        let snapshot = Profile::write_snapshot(root, directory).map_err(|err| {
+            error!("Cannot write snapshot: {:?}", err);
+            err.to_string()
+        });
+        if let Ok(path) = &snapshot {
+            info!("Snapshot written to {:?}", path);
+        }
+        inner.snapshot = Some(snapshot);
+    }
+
+    fn record_modification(&self, path: &Path) {
+        self.inner
+            .lock()
+            .unwrap()
+            .last_modified
+            .insert(path.to_path_buf(), Local::now());
+    }
+
+    fn last_modified(&self, path: &Path) -> Option<DateTime<Local>> {
+        self.inner.lock().unwrap().last_modified.get(path).cloned()
+    }
+
+    fn is_size(&self, attribute_name: &str) -> bool {
+        self.inner.lock().unwrap().settings.is_size(attribute_name)
+    }
+
+    fn staging(&self) -> bool {
+        self.inner.lock().unwrap().staging
+    }
+
+    fn set_staging(&self, staging: bool) {
+        self.inner.lock().unwrap().staging = staging;
+    }
+
+    fn staged(&self) -> BTreeMap<String, AttributeValue> {
+        self.inner.lock().unwrap().staged.clone()
+    }
+
+    fn has_staged(&self) -> bool {
+        !self.inner.lock().unwrap().staged.is_empty()
+    }
+
+    /// Stages the value, `None` removes the Attribute from the staged changes
+    fn stage(&self, name: &str, value: Option<AttributeValue>) {
+        let mut inner = self.inner.lock().unwrap();
+        match value {
+            Some(value) => inner.staged.insert(name.to_string(), value),
+            None => inner.staged.remove(name),
+        };
+        inner.recovery_dirty = true;
+    }
+
+    fn staged_value(&self, name: &str) -> Option<AttributeValue> {
+        self.inner.lock().unwrap().staged.get(name).cloned()
+    }
+
+    fn discard_staged(&self) {
+        self.clear_staged();
+        self.show_message("Staged changes discarded".to_string());
+    }
+
+    /// Drops the staged changes along with their recovery file
+    fn clear_staged(&self) {
+        self.inner.lock().unwrap().staged.clear();
+        self.remove_recovery();
+    }
+
+    /// Writes the staged changes to the recovery file at most every few seconds unless forced,
+    /// returns `true` while unsaved changes are left
+    fn save_recovery(&self, controls: &[Control<Attribute>], force: bool) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        if !inner.recovery_dirty {
+            return false;
+        }
+        if !force && inner.last_recovery_save.elapsed() < RECOVERY_SAVE_INTERVAL {
+            return true;
+        }
+        inner.recovery_dirty = false;
+        inner.last_recovery_save = Instant::now();
+        if inner.staged.is_empty() {
+            drop(inner);
+            self.remove_recovery();
+            return false;
+        }
+        let attributes = inner
+            .staged
+            .iter()
+            .filter_map(|(name, value)| {
+                let control = controls
+                    .iter()
+                    .find(|control| control.attribute().name() == name)?;
+                let entry = ProfileEntry {
+                    attribute_type: control.attribute().attribute_type().to_string(),
+                    current_value: value.clone(),
+                    default_value: None,
+                    meta: None,
+                };
+                Some((name.clone(), entry))
+            })
+            .collect();
+        let profile = Profile {
+            attributes,
+            ..Default::default()
+        };
+        let path = recovery_file_path();
+        match profile.write(&path) {
+            Ok(()) => inner.recovery_path = Some(path),
+            Err(err) => warn!("Cannot save staged changes to {:?}: {}", path, err),
+        }
+        false
+    }
+
+    fn recovery_offer(&self) -> Option<Profile> {
+        self.inner.lock().unwrap().recovery_offer.clone()
+    }
+
+    fn remove_recovery(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.recovery_offer = None;
+        if let Some(path) = inner.recovery_path.take() {
+            match fs::remove_file(&path) {
+                Err(err) if err.kind() != ErrorKind::NotFound => {
+                    warn!("Cannot remove recovery file {:?}: {}", path, err)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Stages the recovered values of the Attributes still present with the same type
+    fn restore_staged(&self, profile: &Profile, controls: &[Control<Attribute>]) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.recovery_offer = None;
+        let mut skipped = Vec::new();
+        for (name, entry) in &profile.attributes {
+            if controls.iter().any(|control| {
+                control.attribute().name() == name
+                    && control.attribute().attribute_type() == entry.attribute_type
+            }) {
+                inner
+                    .staged
+                    .insert(name.clone(), entry.current_value.clone());
+            } else {
+                skipped.push(name.clone());
+            }
+        }
+        inner.staging = true;
+        inner.recovery_dirty = true;
+        let restored = inner.staged.len();
+        drop(inner);
+        if skipped.is_empty() {
+            self.show_message(format!("Restored {} staged changes", restored));
+        } else {
+            self.show_message(format!(
+                "Restored {} staged changes, skipped as not found: {}",
+                restored,
+                skipped.join(", ")
+            ));
+        }
+    }
+
+    fn apply_staged_confirmation(&self) -> bool {
+        self.inner.lock().unwrap().apply_staged_confirmation
+    }
+
+    fn set_apply_staged_confirmation(&self, confirmation: bool) {
+        self.inner.lock().unwrap().apply_staged_confirmation = confirmation;
+    }
+
+    fn poll_interval(&self) -> Option<Duration> {
+        self.inner.lock().unwrap().settings.poll_interval
+    }
+
+    fn is_poll_due(&self, interval: Duration) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        let due = inner.last_poll.elapsed() >= interval;
+        if due {
+            inner.last_poll = Instant::now();
+        }
+        due
+    }
+
+    fn external_change(&self, path: &Path) -> Option<String> {
+        self.inner
+            .lock()
+            .unwrap()
+            .external_changes
+            .get(path)
+            .cloned()
+    }
+
+    fn accept_external_change(&self, path: &Path) {
+        self.inner.lock().unwrap().external_changes.remove(path);
+    }
+
+    fn cache_ttl(&self) -> Option<Duration> {
+        self.inner.lock().unwrap().settings.cache_ttl
+    }
+
+    fn read_only(&self) -> bool {
+        self.inner.lock().unwrap().settings.read_only
+    }
+
+    fn ordered_list_duplicates(&self) -> bool {
+        self.inner.lock().unwrap().settings.ordered_list_duplicates
+    }
+
+    fn group_separator(&self) -> Option<char> {
+        self.inner.lock().unwrap().settings.group_separator
+    }
+
+    fn knowledge_base_warnings(&self, attribute_name: &str) -> Vec<String> {
+        self.inner
+            .lock()
+            .unwrap()
+            .settings
+            .knowledge_base
+            .warnings(attribute_name)
+    }
+
+    /// Performs the follow-up writes declared in the knowledge base and reports them
+    fn write_companions(&self, root: &Path, attribute_name: &str) {
+        let knowledge_base = self.inner.lock().unwrap().settings.knowledge_base.clone();
+        let results = knowledge_base.write_companions(root, attribute_name);
+        if results.is_empty() {
+            return;
+        }
+        for (companion, result) in results {
+            self.handle_result_with_message(
+                result,
+                &format!(
+                    "Companion Attribute {:?} written after {:?}",
+                    companion, attribute_name
+                ),
+            );
+        }
+        // Controls of the companions hold cached values
+        self.inner.lock().unwrap().refresh_requested = true;
+    }
+
+    fn request_confirmation(&self, pending_write: PendingWrite) {
+        self.inner.lock().unwrap().pending_write = Some(pending_write);
+    }
+
+    fn pending_write(&self) -> Option<PendingWrite> {
+        self.inner.lock().unwrap().pending_write.clone()
+    }
+
+    fn take_pending_write(&self) -> Option<PendingWrite> {
+        self.inner.lock().unwrap().pending_write.take()
+    }
+
+    /// True when the window regained focus, no frame was drawn for too long
+    /// or a refresh was requested
+    fn is_stale(&self, focused: bool) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        let now = Instant::now();
+        let stale = (focused && !inner.focused)
+            || now.duration_since(inner.last_update) > inner.settings.refresh_after
+            || std::mem::take(&mut inner.refresh_requested);
+        inner.focused = focused;
+        inner.last_update = now;
+        stale
+    }
+
+    /// Disables all writes without leaving the authenticated session
+    pub fn view_only(&self) -> bool {
+        self.inner.lock().unwrap().view_only
+    }
+
+    fn set_view_only(&self, view_only: bool) {
+        self.inner.lock().unwrap().view_only = view_only;
+    }
+
+    fn dark_mode(&self) -> Option<bool> {
+        self.inner.lock().unwrap().dark_mode
+    }
+
+    fn set_dark_mode(&self, dark_mode: bool) {
+        self.inner.lock().unwrap().dark_mode = Some(dark_mode);
+    }
+
+    fn last_root(&self) -> Option<PathBuf> {
+        self.inner.lock().unwrap().last_root.clone()
+    }
+
+    fn set_last_root(&self, root: &Path) {
+        self.inner.lock().unwrap().last_root = Some(root.to_path_buf());
+    }
+
+    fn display_language(&self) -> Option<String> {
+        self.inner.lock().unwrap().display_language.clone()
+    }
+
+    fn debug_ui(&self) -> bool {
+        self.inner.lock().unwrap().settings.debug_ui
+    }
+
+    fn restore(&self, storage: &dyn eframe::Storage) {
+        let stored: HashMap<PathBuf, String> = storage
+            .get_string(STORAGE_KEY_LAST_MODIFIED)
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        let last_modified = stored
+            .into_iter()
+            .filter_map(|(path, timestamp)| {
+                DateTime::parse_from_rfc3339(&timestamp)
+                    .map(|timestamp| (path, timestamp.with_timezone(&Local)))
+                    .ok()
+            })
+            .collect();
+        let mut inner = self.inner.lock().unwrap();
+        inner.last_modified = last_modified;
+        if inner.settings.remember_root {
+            inner.last_root = storage.get_string(STORAGE_KEY_LAST_ROOT).map(PathBuf::from);
+        }
+        if inner.settings.theme.is_none() {
+            inner.dark_mode = storage
+                .get_string(STORAGE_KEY_THEME)
+                .map(|theme| theme == "dark");
+        }
+        if let Some(path) = storage
+            .get_string(STORAGE_KEY_RECOVERY)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+        {
+            match Profile::from_file(&path) {
+                Ok(profile) if !profile.attributes.is_empty() => {
+                    inner.recovery_offer = Some(profile);
+                    inner.recovery_path = Some(path);
+                }
+                Ok(_) => {}
+                Err(err) => info!("Recovery file {:?} not restored: {}", path, err),
+            }
+        }
+    }
+
+    fn save(&self, storage: &mut dyn eframe::Storage) {
+        let stored: HashMap<PathBuf, String> = self
+            .inner
+            .lock()
+            .unwrap()
+            .last_modified
+            .iter()
+            .map(|(path, timestamp)| (path.clone(), timestamp.to_rfc3339()))
+            .collect();
+        match serde_json::to_string(&stored) {
+            Ok(json) => storage.set_string(STORAGE_KEY_LAST_MODIFIED, json),
+            Err(err) => warn!("Cannot save last modified timestamps: {:?}", err),
+        }
+        let inner = self.inner.lock().unwrap();
+        if let (true, Some(root)) = (inner.settings.remember_root, &inner.last_root) {
+            storage.set_string(STORAGE_KEY_LAST_ROOT, root.to_string_lossy().to_string());
+        }
+        if let Some(dark_mode) = inner.dark_mode {
+            let theme = if dark_mode { "dark" } else { "light" };
+            storage.set_string(STORAGE_KEY_THEME, theme.to_string());
+        }
+        // Storage has no removal, an empty path means no recovery file
+        storage.set_string(
+            STORAGE_KEY_RECOVERY,
+            inner
+                .recovery_path
+                .as_ref()
+                .map_or(String::new(), |path| path.to_string_lossy().to_string()),
+        );
+    }
+}
+
+impl Default for Status {
+    fn default() -> Self {
+        Self::new(Settings::default())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct StatusInner {
+    changed: DateTime<Local>,
+    message: StatusMessage,
+    reboot_required: bool,
+    session_changes: Vec<String>,
+    /// Attribute names with the values before the successful writes, the last one on top
+    undo_stack: Vec<(String, AttributeValue)>,
+    apply_and_reboot_confirmation: bool,
+    reset_all_confirmation: bool,
+    /// Changes are collected in `staged` and written together on apply
+    staging: bool,
+    /// Values by Attribute name, not written yet
+    staged: BTreeMap<String, AttributeValue>,
+    apply_staged_confirmation: bool,
+    /// Recovery file with the staged changes, `None` when nothing is saved
+    recovery_path: Option<PathBuf>,
+    /// Staged changes differ from the recovery file
+    recovery_dirty: bool,
+    last_recovery_save: Instant,
+    /// Staged changes of a previous session that ended without applying them
+    recovery_offer: Option<Profile>,
+    /// Path edited in the export window, `None` when the window is closed
+    export_path: Option<String>,
+    /// Path edited in the import window, `None` when the window is closed
+    import_path: Option<String>,
+    /// Machine mismatches of the profile to confirm before the import, `None` until checked
+    import_warnings: Option<Vec<String>>,
+    /// Fields of the password change window, `None` when the window is closed
+    password_change: Option<PasswordChange>,
+    power_confirmation: Option<PowerAction>,
+    /// Countdown started by the confirmation, `None` until confirmed
+    power_deadline: Option<Instant>,
+    reboot_fallback: bool,
+    write_failure: Option<(String, Option<i32>)>,
+    /// Taken at most once per session, `Err` keeps the failure reason
+    snapshot: Option<Result<PathBuf, String>>,
+    kernel_warning: Option<String>,
+    pending_write: Option<PendingWrite>,
+    last_update: Instant,
+    last_poll: Instant,
+    /// Formatted firmware values of the Attributes changed outside of the Editor
+    external_changes: HashMap<PathBuf, String>,
+    focused: bool,
+    view_only: bool,
+    /// Selected language of the display names, the firmware default when `None`
+    display_language: Option<String>,
+    /// Root selected in this or the previous session, persisted when remembering is enabled
+    last_root: Option<PathBuf>,
+    /// Selected visuals, the system theme when `None`
+    dark_mode: Option<bool>,
+    types_check: bool,
+    refresh_requested: bool,
+    last_modified: HashMap<PathBuf, DateTime<Local>>,
+    settings: Settings,
+}
+
+/// Shared with the worker thread reading the Attributes
+#[derive(Default)]
+pub struct Loading {
+    read: usize,
+    total: usize,
+    result: Option<Result<LoadedAttributes, AttributeError>>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct PasswordChange {
+    current: String,
+    new: String,
+    confirmation: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum PowerAction {
+    Reboot,
+    Shutdown,
+}
+
+impl PowerAction {
+    fn label(&self) -> &'static str {
+        match self {
+            PowerAction::Reboot => "Reboot",
+            PowerAction::Shutdown => "Shutdown",
+        }
+    }
+}
+
+/// Write postponed until the user confirms the warnings
+#[derive(Debug, Clone)]
+pub struct PendingWrite {
+    pub name: String,
+    pub display_name: String,
+    pub value: AttributeValue,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+enum StatusMessage {
+    Ok,
+    Message(String),
+    Error(String),
+}
+
+pub enum AccessMode<T: AttributeParser> {
+    ReadOnly,
+    ReadWrite,
+    ReadWriteAuthenticated(T::Auth),
+}
+
+impl<T: AttributeParser> AccessMode<T> {
+    pub fn write_access(&self) -> bool {
+        match self {
+            AccessMode::ReadOnly => false,
+            _ => true,
+        }
+    }
+
+    pub fn lock_hint(&self) -> &'static str {
+        match self {
+            AccessMode::ReadOnly => "Read only, login to unlock",
+            AccessMode::ReadWrite => "Writable, BIOS is not protected by password",
+            AccessMode::ReadWriteAuthenticated(_) => "Unlocked by authentication",
+        }
+    }
+}
+
+impl eframe::App for Application<Attribute> {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::TopBottomPanel::top("Header").show(ctx, |ui| {
+            self.header_bar(ui);
+        });
+        egui::TopBottomPanel::bottom("Status").show(ctx, |ui| {
+            self.status_bar(ui);
+        });
+        // Values could be changed outside of the controls, for example during sleep
+        if let Application::BiosAttributes { status, .. } = self {
+            if status.is_stale(ctx.input(|i| i.focused)) {
+                info!("Refreshing stale values");
+                self.refresh();
+            }
+        }
+        if let Application::BiosAttributes {
+            access_mode,
+            controls,
+            status,
+            ..
+        } = self
+        {
+            // Expired values are read again only when a frame is drawn
+            if let Some(ttl) = status.cache_ttl() {
+                ctx.request_repaint_after(ttl);
+            }
+            if status.save_recovery(controls, false) {
+                ctx.request_repaint_after(RECOVERY_SAVE_INTERVAL);
+            }
+            if let Some(interval) = status.poll_interval() {
+                if status.is_poll_due(interval) {
+                    Self::poll_external_changes(controls, status);
+                }
+                ctx.request_repaint_after(interval);
+            }
+            if status.take_types_check() {
+                Self::redetect_types(controls, access_mode, status);
+            }
+            if status.debug_ui() {
+                egui::SidePanel::right("Debug").show(ctx, |ui| {
+                    Self::debug_panel(ui, controls);
+                });
+            }
+        }
+        egui::CentralPanel::default().show(ctx, |ui| match self {
+            Application::BiosAdminAuthentication { .. } => {
+                self.bios_admin_authentication_ui(ui);
+            }
+            Application::BiosAttributes {
+                loading: Some(_), ..
+            } => {
+                self.loading_ui(ui);
+            }
+            Application::BiosAttributes { .. } => {
+                self.attributes_edit_form(ui);
+            }
+            Application::SelectRoot { roots, status } => {
+                if roots.len() == 1 {
+                    let root = roots.first().unwrap();
+                    if let Some(state) = status.handle_result_with_message(
+                        Self::bios_admin_authentication(root, status),
+                        &format!("The Only Root {:?} was selected automatically", root),
+                    ) {
+                        *self = state;
+                        ctx.request_repaint();
+                    }
+                } else {
+                    self.select_root_ui(ui);
+                }
+            }
+        });
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        if let Application::BiosAttributes {
+            controls, status, ..
+        } = self
+        {
+            status.save_recovery(controls, true);
+        }
+        self.status().save(storage);
+    }
+
+    fn on_exit(&mut self, _gl: Option<&Context>) {
+        if let Self::BiosAttributes {
+            access_mode: AccessMode::ReadWriteAuthenticated(auth),
+            ..
+        } = self
+        {
+            // Logout
+            let _ = auth.authenticate_with_password("");
+        }
+    }
+}
+
+impl Application<Attribute> {
+    /// Opens the last selected root when it still exists, otherwise keeps the autodetected list
+    pub fn restore(&mut self, storage: &dyn eframe::Storage) {
+        let status = self.status();
+        status.restore(storage);
+        if let (Application::SelectRoot { .. }, Some(root)) = (&self, status.last_root()) {
+            if root.exists() {
+                match Self::bios_admin_authentication(&root, &status) {
+                    Ok(state) => *self = state,
+                    Err(err) => info!("Last root {:?} not restored: {}", root, err),
+                }
+            }
+        }
+    }
+
+    pub fn bios_attributes(
+        path: &Path,
+        access_mode: AccessMode<Attribute>,
+        status: &Status,
+    ) -> Result<Self, AttributeError> {
+        if !is_firmware_attributes_root(path) {
+            return Err(AttributeError::InvalidRoot(path.to_path_buf()));
+        }
+        let loading = Arc::new(Mutex::new(Loading::default()));
+        let worker = loading.clone();
+        let root = path.to_path_buf();
+        thread::spawn(move || {
+            let result = read_attributes_with_progress(&root, |read, total| {
+                let mut loading = worker.lock().unwrap();
+                loading.read = read;
+                loading.total = total;
+            });
+            worker.lock().unwrap().result = Some(result);
+        });
+        let authentications = Attribute::authentications_names(path)?
+            .iter()
+            .filter_map(|name| Attribute::authentication(path, name).ok())
+            .collect();
+        Self::check_pending_reboot(path, status);
+        Ok(Self::BiosAttributes {
+            root: path.to_path_buf(),
+            access_mode,
+            controls: Vec::new(),
+            unsupported: Vec::new(),
+            loading: Some(loading),
+            authentications,
+            authentication_toggle: None,
+            details: None,
+            filter: String::new(),
+            filter_possible_values: false,
+            sort: AttributeSort::default(),
+            prefix_groups: false,
+            modified_only: false,
+            status: status.clone(),
+        })
+    }
+
+    fn controls(
+        root: &Path,
+        attributes: LoadedAttributes,
+        access_mode: &AccessMode<Attribute>,
+        status: &Status,
+    ) -> (Vec<Control<Attribute>>, Vec<(String, String)>) {
+        let mut unsupported = Vec::new();
+        let mut controls: Vec<Control<Attribute>> = attributes
+            .into_iter()
+            .filter_map(|(name, attribute)| {
+                attribute
+                    .map_err(|err| {
+                        warn!("Attribute {:?} skipped: {}", name, err);
+                        unsupported.push((name, err.to_string()));
+                    })
+                    .ok()
+            })
+            .map(|attribute| Control::new(attribute, status, access_mode))
+            .collect();
+        // Parse failures on old kernels are usually caused by the older sysfs layout
+        status.inner.lock().unwrap().kernel_warning = if !unsupported.is_empty() {
+            kernel_compatibility_warning(root)
+        } else {
+            None
+        };
+        // Firmware order, Attributes without it keep the directory order at the end
+        controls.sort_by_key(|control| control.attribute().order().unwrap_or(i32::MAX));
+        (controls, unsupported)
+    }
+
+    /// Progress of the worker thread, the controls are created when it finishes
+    fn loading_ui(&mut self, ui: &mut egui::Ui) {
+        if let Application::BiosAttributes {
+            root,
+            access_mode,
+            controls,
+            unsupported,
+            loading: loading @ Some(_),
+            status,
+            ..
+        } = self
+        {
+            let (read, total, result) = {
+                let mut loading = loading.as_ref().unwrap().lock().unwrap();
+                (loading.read, loading.total, loading.result.take())
+            };
+            if let Some(result) = result {
+                if let Some(attributes) = status.handle_result(result) {
+                    (*controls, *unsupported) =
+                        Self::controls(root, attributes, access_mode, status);
+                }
+                *loading = None;
+            } else {
+                ui.label("Reading attributes…");
+                ui.add(
+                    egui::ProgressBar::new(if total > 0 {
+                        read as f32 / total as f32
+                    } else {
+                        0.0
+                    })
+                    .text(format!("{} / {}", read, total)),
+                );
+                ui.ctx().request_repaint();
+            }
+        }
+    }
+
+    /// Re-reads all current values and the pending reboot state
+    pub fn refresh(&self) {
+        if let Application::BiosAttributes {
+            root,
+            controls,
+            status,
+            ..
+        } = self
+        {
+            for control in controls {
+                control.attribute().clear_current_value_cache();
+            }
+            Self::check_pending_reboot(root, status);
+        }
+    }
+
+    /// Compares the cached values with the firmware, errors are reported on the regular read
+    fn poll_external_changes(controls: &[Control<Attribute>], status: &Status) {
+        let changes = controls
+            .iter()
+            .filter_map(|control| {
+                let attribute = control.attribute();
+                let value = attribute.external_change().ok().flatten()?;
+                Some((
+                    attribute.path().to_path_buf(),
+                    attribute.format_value(&value),
+                ))
+            })
+            .collect();
+        status.inner.lock().unwrap().external_changes = changes;
+    }
+
+    /// Rebuilds the controls of Attributes whose type changed after a write
+    fn redetect_types(
+        controls: &mut [Control<Attribute>],
+        access_mode: &AccessMode<Attribute>,
+        status: &Status,
+    ) {
+        for control in controls.iter_mut() {
+            if !control.attribute().is_type_changed() {
+                continue;
+            }
+            let path = control.attribute().path().to_path_buf();
+            info!("Type of Attribute {:?} changed, reloading", path);
+            if let Some(attribute) = status.handle_result(Attribute::try_from(path)) {
+                *control = Control::new(attribute, status, access_mode);
+            }
+        }
+    }
+
+    pub fn bios_admin_authentication(path: &Path, status: &Status) -> Result<Self, AttributeError> {
+        if status.read_only() {
+            return Self::bios_attributes(path, AccessMode::ReadOnly, status);
+        }
+        // Missing and empty authentication directories mean the same
+        let names = Attribute::authentications_names(path)?;
+        if names.is_empty() {
+            info!(
+                "Root {:?} has no authentications, treated as not protected",
+                path
+            );
+            return Self::bios_attributes(path, AccessMode::ReadWrite, status);
+        }
+        let mut enabled = Vec::new();
+        for name in names {
+            let authentication = Attribute::authentication(path, &name)?;
+            if authentication.is_enabled {
+                enabled.push(authentication);
+            }
+        }
+        let preferred = enabled
+            .iter()
+            .find(|auth| matches!(auth.role, Role::BiosAdmin))
+            .or(enabled.first());
+        match preferred {
+            Some(authentication) => Ok(Self::BiosAdminAuthentication {
+                root: path.to_path_buf(),
+                authentication: authentication.clone(),
+                password: String::new(),
+                failed_attempts: 0,
+                enabled,
+                status: status.clone(),
+            }),
+            None => Self::bios_attributes(path, AccessMode::ReadWrite, status),
+        }
+    }
+
+    fn bios_admin_authentication_ui(&mut self, ui: &mut egui::Ui) {
+        ui.vertical_centered(|ui| {
             ui.allocate_ui_with_layout(
                 [200.0, 10.0].into(),
                 egui::Layout::centered_and_justified(egui::Direction::TopDown),
@@ -242,129 +1640,1124 @@ impl Application<Attribute> {
                         root,
                         authentication,
                         password,
+                        failed_attempts,
+                        enabled,
                         status,
                     } = self
                     {
-                        ui.label(format!("Login: {}", &authentication.login));
-                        ui.label(format!("Role: {:?}", &authentication.role));
-                        ui.label("BIOS Administrator Password: ");
-                        let input_response =
-                            ui.add(egui::TextEdit::singleline(password).password(true));
-                        if ui.memory(|m| m.focus().is_none()) {
-                            input_response.request_focus();
+                        ui.label(format!("Login: {}", &authentication.login));
+                        if enabled.len() > 1 {
+                            let mut selected = authentication.login.clone();
+                            egui::ComboBox::from_label("Role")
+                                .selected_text(format!("{:?}", &authentication.role))
+                                .show_ui(ui, |ui| {
+                                    for auth in enabled.iter() {
+                                        ui.selectable_value(
+                                            &mut selected,
+                                            auth.login.clone(),
+                                            format!("{:?} ({})", auth.role, auth.login),
+                                        );
+                                    }
+                                });
+                            if selected != authentication.login {
+                                if let Some(auth) = enabled.iter().find(|a| a.login == selected) {
+                                    *authentication = auth.clone();
+                                    password.clear();
+                                    *failed_attempts = 0;
+                                }
+                            }
+                        } else {
+                            ui.label(format!("Role: {:?}", &authentication.role));
+                        }
+                        let certificate =
+                            matches!(authentication.mechanism, Mechanism::Certificate);
+                        let input_response = if certificate {
+                            ui.label("Signature file: ");
+                            ui.add(
+                                egui::TextEdit::singleline(password)
+                                    .hint_text("Path to the signed signature"),
+                            )
+                        } else {
+                            ui.label("BIOS Administrator Password: ");
+                            ui.add(
+                                egui::TextEdit::singleline(password)
+                                    .password(true)
+                                    .char_limit(authentication.max_password_length),
+                            )
+                        };
+                        if ui.memory(|m| m.focus().is_none()) {
+                            input_response.request_focus();
+                        }
+                        // The firmware rejects such passwords anyway
+                        let length_valid = if certificate {
+                            !password.is_empty()
+                        } else {
+                            authentication.is_password_length_valid(password)
+                        };
+                        if !length_valid && !certificate {
+                            ui.small(
+                                RichText::new(format!(
+                                    "Password length must be {}..={}",
+                                    authentication.min_password_length,
+                                    authentication.max_password_length
+                                ))
+                                .color(ui.visuals().warn_fg_color),
+                            );
+                        }
+                        if let Some(max) = authentication
+                            .max_password_retries
+                            .filter(|_| *failed_attempts > 0)
+                        {
+                            ui.small(
+                                RichText::new(format!(
+                                    "{} attempts remaining before the firmware locks",
+                                    max.saturating_sub(*failed_attempts)
+                                ))
+                                .color(ui.visuals().warn_fg_color),
+                            );
+                        }
+                        if ui
+                            .add_enabled(length_valid, egui::Button::new("Login"))
+                            .clicked()
+                            || (length_valid
+                                && input_response.has_focus()
+                                && ui.input(|i| i.key_pressed(Key::Enter)))
+                        {
+                            let result = if certificate {
+                                authentication.authenticate_with_certificate(Path::new(password))
+                            } else {
+                                authentication.authenticate_with_password(password)
+                            };
+                            if let (Err(err), Some(max)) =
+                                (&result, authentication.max_password_retries)
+                            {
+                                *failed_attempts += 1;
+                                status.show_error(format!(
+                                    "{}. {} attempts remaining",
+                                    err,
+                                    max.saturating_sub(*failed_attempts)
+                                ));
+                            } else if status.handle_result(result).is_some() {
+                                let access_mode =
+                                    AccessMode::ReadWriteAuthenticated(authentication.clone());
+                                if let Some(state) = status.handle_result_with_message(
+                                    Self::bios_attributes(root, access_mode, status),
+                                    "Logged in",
+                                ) {
+                                    *self = state;
+                                }
+                            }
+                        } else if ui.button("Proceed without Authentication").clicked() {
+                            let access_mode = AccessMode::ReadOnly;
+                            if let Some(state) = status.handle_result_with_message(
+                                Self::bios_attributes(root, access_mode, status),
+                                "Read only mode",
+                            ) {
+                                *self = state;
+                            }
+                        }
+                    }
+                },
+            )
+        });
+    }
+
+    fn attributes_edit_form(&mut self, ui: &mut egui::Ui) {
+        // Stable id keeps the scroll offset when the controls are rebuilt
+        egui::ScrollArea::vertical()
+            .id_source("Attributes Scroll Area")
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                if let Application::BiosAttributes {
+                    root,
+                    access_mode,
+                    controls,
+                    authentications,
+                    authentication_toggle,
+                    details,
+                    filter,
+                    filter_possible_values,
+                    sort,
+                    prefix_groups,
+                    modified_only,
+                    unsupported,
+                    status,
+                    ..
+                } = self
+                {
+                    let status = status.clone();
+                    Self::authentications_ui(
+                        ui,
+                        root,
+                        access_mode,
+                        authentications,
+                        authentication_toggle,
+                        &status,
+                    );
+                    let shortcuts = Shortcuts::read(ui);
+                    if shortcuts.refresh {
+                        // Performed on the next frame, like after a focus change
+                        status.inner.lock().unwrap().refresh_requested = true;
+                        ui.ctx().request_repaint();
+                    }
+                    if shortcuts.reset_all && access_mode.write_access() && !status.view_only() {
+                        status.set_reset_all_confirmation(true);
+                    }
+                    if shortcuts.clear_filter {
+                        filter.clear();
+                    }
+                    // Focused controls are drawn with the active visuals, a wider stroke makes
+                    // the keyboard focus visible
+                    let selection = ui.visuals().selection.stroke.color;
+                    ui.visuals_mut().widgets.active.bg_stroke = egui::Stroke::new(2.0, selection);
+                    ui.horizontal(|ui| {
+                        ui.label("🔍");
+                        let search = ui
+                            .add(
+                                egui::TextEdit::singleline(filter)
+                                    .hint_text("Search attributes")
+                                    .desired_width(240.0),
+                            )
+                            .on_hover_text("Focus with /, clear with Esc");
+                        if shortcuts.focus_search {
+                            search.request_focus();
+                        }
+                        if ui.small_button("✖").clicked() {
+                            filter.clear();
+                        }
+                        ui.checkbox(filter_possible_values, "Search possible values");
+                        egui::ComboBox::from_id_source("Attributes Sort")
+                            .selected_text(sort.label())
+                            .show_ui(ui, |ui| {
+                                for value in AttributeSort::ALL {
+                                    ui.selectable_value(sort, value, value.label());
+                                }
+                            });
+                        ui.checkbox(prefix_groups, "Group by prefix");
+                        ui.checkbox(modified_only, "Show modified only")
+                            .on_hover_text(
+                                "Attributes whose current value differs from the default value",
+                            );
+                    });
+                    let mut visible: Vec<&Control<Attribute>> = controls
+                        .iter()
+                        .filter(|control| {
+                            matches_filter(control.attribute(), filter, *filter_possible_values)
+                                && (!*modified_only || is_modified(control.attribute()))
+                        })
+                        .collect();
+                    sort.sort(&mut visible);
+                    let mut changed = false;
+                    if controls.is_empty() {
+                        ui.weak(format!(
+                            "No firmware attributes found at {:?}",
+                            root.join("attributes")
+                        ));
+                    } else if visible.is_empty() && *modified_only {
+                        ui.weak("No attributes differ from their default values");
+                    } else if visible.is_empty() && !filter.is_empty() {
+                        ui.weak(format!("No attributes match {:?}", filter));
+                    } else if *prefix_groups {
+                        let separator = status.group_separator();
+                        for (prefix, controls) in group_by_prefix(&visible, separator) {
+                            egui::CollapsingHeader::new(prefix)
+                                .id_source(("Prefix", prefix))
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    changed = Self::attributes_grid(ui, prefix, &controls, details)
+                                        || changed;
+                                });
+                        }
+                    } else if visible.iter().any(|c| c.attribute().category().is_some()) {
+                        for (category, controls) in group_by_category(&visible) {
+                            egui::CollapsingHeader::new(category)
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    changed =
+                                        Self::attributes_grid(ui, category, &controls, details)
+                                            || changed;
+                                });
+                        }
+                    } else {
+                        changed = Self::attributes_grid(ui, "Attributes Grid", &visible, details);
+                    }
+                    if !unsupported.is_empty() {
+                        Self::unsupported_panel(ui, unsupported);
+                    }
+                    if let Some(name) = details.clone() {
+                        let mut open = true;
+                        if let Some(control) =
+                            controls.iter().find(|c| c.attribute().name() == &name)
+                        {
+                            egui::Window::new(control.attribute().display_name())
+                                .id(egui::Id::new("Attribute Details Window"))
+                                .open(&mut open)
+                                .show(ui.ctx(), |ui| {
+                                    control.details_ui(ui);
+                                    if ui.button("Export raw").clicked() {
+                                        let result = profile::export_raw(
+                                            control.attribute(),
+                                            &raw_export_directory(),
+                                        );
+                                        if let Some(path) = status.handle_result(result) {
+                                            status.show_message(format!(
+                                                "Raw properties exported to {:?}",
+                                                path
+                                            ));
+                                        }
+                                    }
+                                    ui.separator();
+                                    egui::Grid::new("Attribute Details Editor")
+                                        .num_columns(3)
+                                        .show(ui, |ui| {
+                                            changed = ui.add(control.clone()).changed() || changed;
+                                        });
+                                });
+                        }
+                        if !open {
+                            details.take();
+                        }
+                    }
+                    if status.apply_staged_confirmation() {
+                        Self::apply_staged_window(ui.ctx(), &status, controls);
+                    }
+                    if let Some(profile) = status.recovery_offer() {
+                        if !controls.is_empty() && access_mode.write_access() && !status.view_only()
+                        {
+                            Self::recovery_window(ui.ctx(), &status, controls, &profile);
+                        }
+                    }
+                    if let Some(path) = status.export_path() {
+                        Self::export_window(ui.ctx(), &status, root, path);
+                    }
+                    if let Some(path) = status.import_path() {
+                        if Self::import_window(ui.ctx(), &status, root, path) {
+                            changed = true;
+                        }
+                    }
+                    if status.reset_all_confirmation() {
+                        changed = Self::reset_all_window(ui.ctx(), &status, controls) || changed;
+                    }
+                    if let Some(pending_write) = status.pending_write() {
+                        changed =
+                            Self::confirmation_window(ui.ctx(), &status, controls, pending_write)
+                                || changed;
+                    }
+                    if changed {
+                        Self::check_pending_reboot(root, &status);
+                    }
+                    // Absence of password authentication doesn't guarantee that writes are allowed
+                    if let (AccessMode::ReadWrite, Some((name, errno))) =
+                        (access_mode, status.write_failure())
+                    {
+                        Self::write_failure_window(
+                            ui.ctx(),
+                            &status,
+                            authentications,
+                            &name,
+                            errno,
+                        );
+                    }
+                }
+            });
+    }
+
+    fn confirmation_window(
+        ctx: &egui::Context,
+        status: &Status,
+        controls: &[Control<Attribute>],
+        pending_write: PendingWrite,
+    ) -> bool {
+        let Some(control) = controls
+            .iter()
+            .find(|control| control.attribute().name() == &pending_write.name)
+        else {
+            status.take_pending_write();
+            return false;
+        };
+        let mut confirmed = false;
+        egui::Window::new("Confirm Change")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                let attribute = control.attribute();
+                ui.label(format!(
+                    "Set {} from {:?} to {:?}?",
+                    pending_write.display_name,
+                    attribute
+                        .current_value()
+                        .map_or("-".to_string(), |value| attribute.format_value(&value)),
+                    attribute.format_value(&pending_write.value)
+                ));
+                for warning in &pending_write.warnings {
+                    ui.label(
+                        RichText::new(format!("⚠ {}", warning)).color(ui.visuals().warn_fg_color),
+                    );
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Apply").clicked() {
+                        status.take_pending_write();
+                        control.write_confirmed(&pending_write.value);
+                        confirmed = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        status.take_pending_write();
+                    }
+                });
+            });
+        confirmed
+    }
+
+    fn export_window(ctx: &egui::Context, status: &Status, root: &Path, mut path: String) {
+        egui::Window::new("Export")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("Export current values of all Attributes to JSON file:");
+                if ui
+                    .add(egui::TextEdit::singleline(&mut path).desired_width(400.0))
+                    .changed()
+                {
+                    status.set_export_path(Some(path.clone()));
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Export").clicked() {
+                        status.set_export_path(None);
+                        if status
+                            .handle_result(profile::export_json(root, Path::new(&path)))
+                            .is_some()
+                        {
+                            status.show_message(format!("Exported to {:?}", path));
+                        }
+                    }
+                    if ui.button("Cancel").clicked() {
+                        status.set_export_path(None);
+                    }
+                });
+            });
+    }
+
+    fn password_change_window(
+        ctx: &egui::Context,
+        status: &Status,
+        auth: &Authentication,
+        mut password_change: PasswordChange,
+    ) {
+        egui::Window::new("Change password")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                let mut changed = false;
+                egui::Grid::new("Password Change Grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        for (label, value) in [
+                            ("Current password", &mut password_change.current),
+                            ("New password", &mut password_change.new),
+                            ("Repeat new password", &mut password_change.confirmation),
+                        ] {
+                            ui.label(label);
+                            changed |= ui
+                                .add(
+                                    egui::TextEdit::singleline(value)
+                                        .password(true)
+                                        .char_limit(auth.max_password_length),
+                                )
+                                .changed();
+                            ui.end_row();
+                        }
+                    });
+                let length_valid = auth.is_password_length_valid(&password_change.new);
+                let confirmed = password_change.new == password_change.confirmation;
+                if !length_valid {
+                    ui.small(
+                        RichText::new(format!(
+                            "Password length must be {}..={}",
+                            auth.min_password_length, auth.max_password_length
+                        ))
+                        .color(ui.visuals().warn_fg_color),
+                    );
+                } else if !confirmed {
+                    ui.small(
+                        RichText::new("Passwords do not match").color(ui.visuals().warn_fg_color),
+                    );
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(length_valid && confirmed, egui::Button::new("Change"))
+                        .clicked()
+                    {
+                        status.set_password_change(None);
+                        let result = auth
+                            .change_password(&password_change.current, &password_change.new)
+                            // The session continues with the new password
+                            .and_then(|_| auth.authenticate_with_password(&password_change.new));
+                        status.handle_result_with_message(result, "Password changed");
+                        changed = false;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        status.set_password_change(None);
+                        changed = false;
+                    }
+                });
+                if changed {
+                    status.set_password_change(Some(password_change));
+                }
+            });
+    }
+
+    fn import_window(ctx: &egui::Context, status: &Status, root: &Path, mut path: String) -> bool {
+        let mut imported = false;
+        let warnings = status.import_warnings();
+        egui::Window::new("Import")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("Apply the values of the exported JSON file:");
+                if ui
+                    .add(egui::TextEdit::singleline(&mut path).desired_width(400.0))
+                    .changed()
+                {
+                    status.set_import_path(Some(path.clone()));
+                }
+                for warning in warnings.iter().flatten() {
+                    ui.label(
+                        RichText::new(format!("⚠ {}", warning)).color(ui.visuals().warn_fg_color),
+                    );
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let label = if warnings.is_some() {
+                        "Import anyway"
+                    } else {
+                        "Import"
+                    };
+                    if ui.button(label).clicked() {
+                        // The first click only checks the profile when it has warnings
+                        let confirmed = match &warnings {
+                            Some(warnings) => Some(warnings.clone()),
+                            None => match status.profile_warnings(root, Path::new(&path)) {
+                                Some(warnings) if !warnings.is_empty() => {
+                                    status.set_import_warnings(warnings);
+                                    None
+                                }
+                                warnings => warnings,
+                            },
+                        };
+                        if let Some(warnings) = confirmed {
+                            status.set_import_path(None);
+                            status.import_json(root, Path::new(&path), &warnings);
+                            imported = true;
+                        }
+                    }
+                    if ui
+                        .button("Export as script")
+                        .on_hover_text(
+                            "Save the writes the import would perform as a shell script \
+                            next to the file, passwords are left as placeholders",
+                        )
+                        .clicked()
+                    {
+                        status.set_import_path(None);
+                        let result = profile::export_script(root, Path::new(&path));
+                        if let Some(script_path) = status.handle_result(result) {
+                            status.show_message(format!("Script written to {:?}", script_path));
                         }
-                        if ui.button("Login").clicked()
-                            || (input_response.has_focus()
-                                && ui.input(|i| i.key_pressed(Key::Enter)))
-                        {
-                            if status
-                                .handle_result(authentication.authenticate_with_password(&password))
-                                .is_some()
-                            {
-                                let access_mode =
-                                    AccessMode::ReadWriteAuthenticated(authentication.clone());
-                                if let Some(state) = status.handle_result_with_message(
-                                    Self::bios_attributes(root, access_mode, status),
-                                    "Logged in",
-                                ) {
-                                    *self = state;
-                                }
-                            }
-                        } else if ui.button("Proceed without Authentication").clicked() {
-                            let access_mode = AccessMode::ReadOnly;
-                            if let Some(state) = status.handle_result_with_message(
-                                Self::bios_attributes(root, access_mode, status),
-                                "Read only mode",
-                            ) {
-                                *self = state;
-                            }
+                    }
+                    if ui.button("Cancel").clicked() {
+                        status.set_import_path(None);
+                    }
+                });
+            });
+        imported
+    }
+
+    fn undo_button(ui: &mut egui::Ui, status: &Status, controls: &[Control<Attribute>]) {
+        let last = status.last_undo();
+        let response = ui.add_enabled(last.is_some(), egui::Button::new("↶ Undo"));
+        let Some((name, value)) = last else {
+            response.on_disabled_hover_text("No changes to undo");
+            return;
+        };
+        let Some(control) = controls.iter().find(|c| c.attribute().name() == &name) else {
+            status.pop_undo();
+            return;
+        };
+        let attribute = control.attribute();
+        if response
+            .on_hover_text(format!(
+                "Write {:?} back to {}",
+                attribute.format_value(&value),
+                attribute.display_name()
+            ))
+            .clicked()
+        {
+            Self::undo(status, control, &value);
+        }
+    }
+
+    /// Writes the previous value back, the entry is removed on failure too
+    fn undo(status: &Status, control: &Control<Attribute>, value: &AttributeValue) {
+        // Removed before the write, so the entry the write records doesn't push another one
+        // out of a full stack. Undo itself is not recorded
+        let len = status.pop_undo();
+        control.write_confirmed(value);
+        status.truncate_undo(len);
+    }
+
+    fn reset_all_window(
+        ctx: &egui::Context,
+        status: &Status,
+        controls: &[Control<Attribute>],
+    ) -> bool {
+        let mut confirmed = false;
+        egui::Window::new("Reset all to defaults")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("Are you sure? All Attributes with a default value will be overwritten.");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Reset all").clicked() {
+                        status.set_reset_all_confirmation(false);
+                        Self::reset_all_to_defaults(status, controls);
+                        confirmed = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        status.set_reset_all_confirmation(false);
+                    }
+                });
+            });
+        confirmed
+    }
+
+    fn reset_all_to_defaults(status: &Status, controls: &[Control<Attribute>]) {
+        let mut reset = 0;
+        let mut failed = Vec::new();
+        for control in controls {
+            match control.reset_to_default_confirmed() {
+                Some(true) => reset += 1,
+                Some(false) => failed.push(control.attribute().display_name().clone()),
+                None => {}
+            }
+        }
+        if failed.is_empty() {
+            status.show_message(format!("Reset {} attributes", reset));
+        } else {
+            status.show_message(format!(
+                "Reset {} attributes, {} failed: {}",
+                reset,
+                failed.len(),
+                failed.join(", ")
+            ));
+        }
+    }
+
+    fn attributes_grid(
+        ui: &mut egui::Ui,
+        id_source: &str,
+        controls: &[&Control<Attribute>],
+        details: &mut Option<String>,
+    ) -> bool {
+        let mut changed = false;
+        egui::Grid::new(id_source)
+            .spacing([20f32, 5f32])
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                for control in controls {
+                    changed = ui.add((*control).clone()).changed() || changed;
+                    if ui.small_button("🔍").on_hover_text("Details").clicked() {
+                        details.replace(control.attribute().name().clone());
+                    }
+                    ui.end_row();
+                }
+            });
+        changed
+    }
+
+    fn authentications_ui(
+        ui: &mut egui::Ui,
+        root: &Path,
+        access_mode: &AccessMode<Attribute>,
+        authentications: &mut [Authentication],
+        authentication_toggle: &mut Option<usize>,
+        status: &Status,
+    ) {
+        if authentications.is_empty() {
+            return;
+        }
+        egui::CollapsingHeader::new("Authentications").show(ui, |ui| {
+            egui::Grid::new("Authentications Grid")
+                .num_columns(4)
+                .striped(true)
+                .show(ui, |ui| {
+                    for (index, authentication) in authentications.iter().enumerate() {
+                        ui.label(&authentication.login);
+                        ui.label(authentication.role.as_ref());
+                        ui.label(authentication.mechanism.as_ref());
+                        let disabled_reason = if !authentication.is_enabled_writable() {
+                            Some("The firmware exposes the enabled state as read-only")
+                        } else if !matches!(access_mode, AccessMode::ReadWriteAuthenticated(_)) {
+                            Some("Login is required to change the enabled state")
+                        } else {
+                            None
+                        };
+                        let mut is_enabled = authentication.is_enabled;
+                        let response = ui.add_enabled(
+                            disabled_reason.is_none(),
+                            egui::Checkbox::new(&mut is_enabled, "Enabled"),
+                        );
+                        if let Some(disabled_reason) = disabled_reason {
+                            response.on_disabled_hover_text(disabled_reason);
+                        } else if response.changed() {
+                            authentication_toggle.replace(index);
                         }
+                        ui.end_row();
                     }
-                },
-            )
+                });
         });
+        if let Some(index) = *authentication_toggle {
+            let login = authentications[index].login.clone();
+            let enabled = !authentications[index].is_enabled;
+            let action = if enabled { "Enable" } else { "Disable" };
+            let mut confirmed = false;
+            let mut cancelled = false;
+            egui::Window::new("Change Authentication State")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ui.ctx(), |ui| {
+                    ui.label(format!(
+                        "{} authentication {:?} ({})?",
+                        action,
+                        login,
+                        authentications[index].role.as_ref()
+                    ));
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        confirmed = ui.button(action).clicked();
+                        cancelled = ui.button("Cancel").clicked();
+                    });
+                });
+            if confirmed
+                && status
+                    .handle_result_with_message(
+                        authentications[index].set_enabled(enabled),
+                        &format!("Authentication {:?} {}d", login, action.to_lowercase()),
+                    )
+                    .is_some()
+            {
+                if let Some(authentication) =
+                    status.handle_result(Attribute::authentication(root, &login))
+                {
+                    authentications[index] = authentication;
+                }
+            }
+            if confirmed || cancelled {
+                authentication_toggle.take();
+            }
+        }
     }
 
-    fn attributes_edit_form(&mut self, ui: &mut egui::Ui) {
-        egui::ScrollArea::vertical()
-            .auto_shrink([false, false])
+    fn write_failure_window(
+        ctx: &egui::Context,
+        status: &Status,
+        authentications: &[Authentication],
+        name: &str,
+        errno: Option<i32>,
+    ) {
+        egui::Window::new("Write failed")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "The firmware rejected the value of Attribute {:?}, \
+                    although no password authentication is enabled.",
+                    name
+                ));
+                if matches!(errno, Some(1) | Some(13)) {
+                    ui.label("Make sure that the Editor is running with the root privileges.");
+                }
+                if authentications.is_empty() {
+                    ui.label(
+                        "The firmware doesn't expose any authentication, \
+                        the Attribute may be locked by the firmware policy.",
+                    );
+                } else {
+                    ui.label("One of the following authentications may be required:");
+                    for authentication in authentications {
+                        ui.label(format!(
+                            "• {} ({}, {})",
+                            authentication.login,
+                            authentication.role.as_ref(),
+                            if authentication.is_enabled {
+                                "enabled"
+                            } else {
+                                "disabled"
+                            }
+                        ));
+                    }
+                }
+                ui.separator();
+                if ui.button("Close").clicked() {
+                    status.clear_write_failure();
+                }
+            });
+    }
+
+    /// Attributes skipped because of parse errors, for example of unsupported types
+    fn unsupported_panel(ui: &mut egui::Ui, unsupported: &[(String, String)]) {
+        ui.separator();
+        egui::CollapsingHeader::new(format!("Unsupported attributes ({})", unsupported.len()))
+            .id_source("Unsupported Attributes")
             .show(ui, |ui| {
-                if let Application::BiosAttributes {
-                    root,
-                    access_mode,
-                    controls,
-                    status,
-                } = self
-                {
-                    let status = status.clone();
-                    let mut changed = false;
-                    ui.add_enabled_ui(access_mode.write_access(), |ui| {
-                        egui::Grid::new("Attributes Grid")
-                            .spacing([20f32, 5f32])
-                            .num_columns(3)
-                            .striped(true)
-                            .show(ui, |ui| {
-                                for control in controls {
-                                    changed = ui.add(control.clone()).changed() || changed;
-                                    ui.end_row();
-                                }
-                            });
+                egui::Grid::new("Unsupported Attributes Grid")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (name, error) in unsupported {
+                            ui.strong(name);
+                            ui.label(error);
+                            ui.end_row();
+                        }
                     });
-                    if changed {
-                        Self::check_pending_reboot(root, &status);
+            });
+    }
+
+    fn debug_panel(ui: &mut egui::Ui, controls: &[Control<Attribute>]) {
+        ui.heading("Value Cache");
+        if ui.button("Clear all caches").clicked() {
+            for control in controls {
+                control.attribute().clear_current_value_cache();
+            }
+        }
+        ui.separator();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("Value Cache Grid")
+                .num_columns(3)
+                .striped(true)
+                .show(ui, |ui| {
+                    for control in controls {
+                        let attribute = control.attribute();
+                        ui.label(attribute.name());
+                        if attribute.is_current_value_cached() {
+                            ui.label("cached");
+                        } else {
+                            ui.weak("empty");
+                        }
+                        if ui.small_button("Clear cache").clicked() {
+                            attribute.clear_current_value_cache();
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+
+    /// Shown only when the firmware exposes localized display names
+    fn language_selector(ui: &mut egui::Ui, status: &Status, controls: &mut [Control<Attribute>]) {
+        let languages: BTreeSet<String> = controls
+            .iter()
+            .flat_map(|control| control.attribute().display_languages())
+            .cloned()
+            .collect();
+        if languages.is_empty() {
+            return;
+        }
+        let mut language = status.display_language();
+        let previous = language.clone();
+        egui::ComboBox::from_label("Language")
+            .selected_text(language.as_deref().unwrap_or("Default"))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut language, None, "Default");
+                for code in languages {
+                    ui.selectable_value(&mut language, Some(code.clone()), code);
+                }
+            });
+        if language != previous {
+            for control in controls.iter_mut() {
+                control.set_display_language(language.clone());
+            }
+            status.inner.lock().unwrap().display_language = language;
+        }
+    }
+
+    fn staging_toggle(ui: &mut egui::Ui, status: &Status) {
+        let mut staging = status.staging();
+        let pending = status.has_staged();
+        if ui
+            .add_enabled(!pending, egui::Checkbox::new(&mut staging, "Stage changes"))
+            .on_hover_text("Collect changes and write them together with Apply")
+            .on_disabled_hover_text("Apply or discard the staged changes first")
+            .changed()
+        {
+            status.set_staging(staging);
+        }
+    }
+
+    /// Lists the staged changes with their warnings, confirmed changes are written in the order
+    /// of the controls
+    fn apply_staged_window(ctx: &egui::Context, status: &Status, controls: &[Control<Attribute>]) {
+        let staged = status.staged();
+        egui::Window::new("Apply Staged Changes")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                let changes: Vec<(&Control<Attribute>, &AttributeValue)> = controls
+                    .iter()
+                    .filter_map(|control| Some((control, staged.get(control.attribute().name())?)))
+                    .collect();
+                for (control, value) in &changes {
+                    let attribute = control.attribute();
+                    ui.label(format!(
+                        "{}: {:?} → {:?}",
+                        attribute.display_name(),
+                        attribute
+                            .current_value()
+                            .map_or("-".to_string(), |value| attribute.format_value(&value)),
+                        attribute.format_value(value)
+                    ));
+                    for warning in control.warnings() {
+                        ui.label(
+                            RichText::new(format!("⚠ {}", warning))
+                                .color(ui.visuals().warn_fg_color),
+                        );
+                    }
+                }
+                let invalid: Vec<String> = changes
+                    .iter()
+                    .filter_map(|(control, value)| {
+                        let err = control.attribute().validate_value(value).err()?;
+                        Some(format!("{}: {}", control.attribute().display_name(), err))
+                    })
+                    .collect();
+                for message in &invalid {
+                    ui.label(
+                        RichText::new(format!("✖ {}", message)).color(ui.visuals().error_fg_color),
+                    );
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let response = ui.add_enabled(invalid.is_empty(), egui::Button::new("Apply"));
+                    let clicked = response.clicked();
+                    response
+                        .on_disabled_hover_text("Fix or discard the invalid staged values first");
+                    if clicked {
+                        status.set_apply_staged_confirmation(false);
+                        let failed: Vec<String> = changes
+                            .iter()
+                            .filter(|(control, value)| !control.write_confirmed(value))
+                            .map(|(control, _)| control.attribute().display_name().clone())
+                            .collect();
+                        status.clear_staged();
+                        if failed.is_empty() {
+                            status.show_message(format!("Applied {} changes", changes.len()));
+                        } else {
+                            status.show_error(format!(
+                                "Applied {} of {} changes, failed: {}",
+                                changes.len() - failed.len(),
+                                changes.len(),
+                                failed.join(", ")
+                            ));
+                        }
                     }
+                    if ui.button("Cancel").clicked() {
+                        status.set_apply_staged_confirmation(false);
+                    }
+                });
+            });
+    }
+
+    /// Offers the staged changes of a session that ended without applying them
+    fn recovery_window(
+        ctx: &egui::Context,
+        status: &Status,
+        controls: &[Control<Attribute>],
+        profile: &Profile,
+    ) {
+        egui::Window::new("Unsaved Changes")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("You have unsaved changes from a previous session — restore?");
+                for (name, entry) in &profile.attributes {
+                    let value = controls
+                        .iter()
+                        .find(|control| control.attribute().name() == name)
+                        .map_or(format!("{:?}", entry.current_value), |control| {
+                            control.attribute().format_value(&entry.current_value)
+                        });
+                    ui.label(format!("{}: {:?}", name, value));
                 }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Restore").clicked() {
+                        status.restore_staged(profile, controls);
+                    }
+                    if ui.button("Discard").clicked() {
+                        status.remove_recovery();
+                    }
+                });
             });
     }
 
+    fn view_only_toggle(ui: &mut egui::Ui, status: &Status) {
+        let mut view_only = status.view_only();
+        if ui
+            .checkbox(&mut view_only, "View only")
+            .on_hover_text("Disable all changes, the session stays logged in")
+            .changed()
+        {
+            status.set_view_only(view_only);
+        }
+    }
+
     fn header_bar(&mut self, ui: &mut egui::Ui) {
         ui.columns(2, |col| {
             col[0].horizontal(|ui| {
                 ui.label(RichText::new("⚙").size(68.0));
                 ui.heading("\n BIOS Configuration Tool\n");
             });
-            col[1].vertical(|ui| match self {
-                Application::BiosAttributes {
-                    root,
-                    status,
-                    access_mode: AccessMode::ReadWriteAuthenticated(auth),
-                    ..
-                } => {
-                    ui.label(format!("Logged in: {}", auth.login));
-                    if ui.button("Logout").clicked() {
-                        let _ = auth.authenticate_with_password("");
-                        if let Some(state) = status.handle_result_with_message(
-                            Self::bios_admin_authentication(root, status),
-                            "Logged out",
-                        ) {
-                            *self = state;
-                        }
+            col[1].vertical(|ui| {
+                let dark_mode = ui.visuals().dark_mode;
+                if ui
+                    .button(if dark_mode { "☀ Light" } else { "🌙 Dark" })
+                    .on_hover_text("Switch the theme, the choice is kept for the next launch")
+                    .clicked()
+                {
+                    self.status().set_dark_mode(!dark_mode);
+                    ui.ctx().set_visuals(visuals(!dark_mode));
+                }
+                if matches!(self, Application::BiosAttributes { .. })
+                    && ui
+                        .button("⟳ Refresh")
+                        .on_hover_text("Read all values from the firmware again")
+                        .clicked()
+                {
+                    self.refresh();
+                }
+                if let Application::BiosAttributes {
+                    controls, status, ..
+                } = self
+                {
+                    Self::language_selector(ui, status, controls);
+                    if ui
+                        .button("Export…")
+                        .on_hover_text("Save current values of all Attributes to JSON file")
+                        .clicked()
+                    {
+                        status.set_export_path(Some(
+                            default_export_path().to_string_lossy().to_string(),
+                        ));
                     }
                 }
-                Application::BiosAttributes {
-                    root,
+                if let Application::BiosAttributes {
+                    access_mode,
+                    controls,
                     status,
-                    access_mode: AccessMode::ReadOnly,
                     ..
-                } => {
-                    ui.label("Not logged in");
-                    if ui.button("Login").clicked() {
-                        if let Some(state) = status.handle_result_with_message(
-                            Self::bios_admin_authentication(root, status),
-                            "Logged out",
-                        ) {
-                            *self = state;
-                        }
+                } = self
+                {
+                    if access_mode.write_access()
+                        && !status.view_only()
+                        && ui
+                            .button("Import…")
+                            .on_hover_text("Apply values from an exported JSON file")
+                            .clicked()
+                    {
+                        status.set_import_path(Some(
+                            profiles_directory()
+                                .join("profile.json")
+                                .to_string_lossy()
+                                .to_string(),
+                        ));
+                    }
+                    if access_mode.write_access()
+                        && !status.view_only()
+                        && ui
+                            .button("Reset all to defaults")
+                            .on_hover_text("Write the default value of every Attribute")
+                            .clicked()
+                    {
+                        status.set_reset_all_confirmation(true);
+                    }
+                    if access_mode.write_access() && !status.view_only() {
+                        Self::undo_button(ui, status, controls);
                     }
                 }
-                Application::BiosAttributes {
-                    access_mode: AccessMode::ReadWrite,
-                    ..
-                } => {
-                    ui.label("Not logged in");
-                    ui.label("BIOS not protected");
+                match self {
+                    Application::BiosAttributes {
+                        root,
+                        status,
+                        access_mode: AccessMode::ReadWriteAuthenticated(auth),
+                        ..
+                    } => {
+                        ui.label(format!("Logged in: {}", auth.login));
+                        Self::view_only_toggle(ui, status);
+                        Self::staging_toggle(ui, status);
+                        if matches!(auth.mechanism, Mechanism::Password)
+                            && ui.button("Change password").clicked()
+                        {
+                            status.set_password_change(Some(PasswordChange::default()));
+                        }
+                        if let Some(password_change) = status.password_change() {
+                            Self::password_change_window(ui.ctx(), status, auth, password_change);
+                        }
+                        if ui.button("Logout").clicked() {
+                            let _ = auth.authenticate_with_password("");
+                            if let Some(state) = status.handle_result_with_message(
+                                Self::bios_admin_authentication(root, status),
+                                "Logged out",
+                            ) {
+                                *self = state;
+                            }
+                        }
+                    }
+                    Application::BiosAttributes {
+                        root,
+                        status,
+                        access_mode: AccessMode::ReadOnly,
+                        ..
+                    } => {
+                        if status.read_only() {
+                            ui.label("Read-only (forced)")
+                                .on_hover_text("Started with --read-only, nothing can be changed");
+                        } else {
+                            ui.label("Not logged in");
+                        }
+                        if !status.read_only() && ui.button("Login").clicked() {
+                            if let Some(state) = status.handle_result_with_message(
+                                Self::bios_admin_authentication(root, status),
+                                "Logged out",
+                            ) {
+                                *self = state;
+                            }
+                        }
+                    }
+                    Application::BiosAttributes {
+                        access_mode: AccessMode::ReadWrite,
+                        status,
+                        ..
+                    } => {
+                        ui.label("Not logged in");
+                        ui.label("BIOS not protected");
+                        Self::view_only_toggle(ui, status);
+                        Self::staging_toggle(ui, status);
+                    }
+                    _ => {}
                 }
-                _ => {}
             });
         });
     }
@@ -376,11 +2769,53 @@ impl Application<Attribute> {
             ui.horizontal(|ui| {
                 ui.small("Changes will be applied after restart.");
                 if ui.small_button("Reboot").clicked() {
-                    status.handle_result_with_message(system_shutdown::reboot(), "Rebooting...");
+                    status.set_power_confirmation(Some(PowerAction::Reboot));
+                }
+                if ui.small_button("Shutdown").clicked() {
+                    status.set_power_confirmation(Some(PowerAction::Shutdown));
+                }
+                if ui.small_button("Apply & Reboot").clicked() {
+                    status.set_apply_and_reboot_confirmation(true);
+                }
+            });
+            ui.separator();
+        }
+        if !inner.staged.is_empty() {
+            ui.horizontal(|ui| {
+                ui.small(format!("{} pending changes", inner.staged.len()));
+                if ui.small_button("Apply…").clicked() {
+                    status.set_apply_staged_confirmation(true);
+                }
+                if ui.small_button("Discard").clicked() {
+                    status.discard_staged();
                 }
             });
             ui.separator();
         }
+        if inner.apply_and_reboot_confirmation {
+            Self::apply_and_reboot_window(ui.ctx(), &status, &inner.session_changes);
+        }
+        if let Some(action) = inner.power_confirmation {
+            Self::power_confirmation_window(ui.ctx(), &status, action, inner.power_deadline);
+        }
+        if inner.reboot_fallback {
+            Self::reboot_fallback_window(ui.ctx(), &status);
+        }
+        if let Some(warning) = &inner.kernel_warning {
+            ui.small(RichText::new(warning).color(ui.style().visuals.warn_fg_color));
+        }
+        match &inner.snapshot {
+            Some(Ok(path)) => {
+                ui.small(format!("Snapshot before changes: {}", path.display()));
+            }
+            Some(Err(err)) => {
+                ui.small(
+                    RichText::new(format!("Snapshot failed: {}", err))
+                        .color(ui.style().visuals.warn_fg_color),
+                );
+            }
+            None => {}
+        }
         ui.horizontal(|ui| {
             ui.small(inner.changed.format("%d/%m/%Y %H:%M:%S").to_string());
             match inner.message {
@@ -398,10 +2833,123 @@ impl Application<Attribute> {
         });
     }
 
+    fn apply_and_reboot_window(ctx: &egui::Context, status: &Status, changes: &[String]) {
+        egui::Window::new("Apply & Reboot")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                if changes.is_empty() {
+                    ui.label("No changes were made in this session.");
+                } else {
+                    ui.label("The following changes will be applied after reboot:");
+                    egui::ScrollArea::vertical()
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            for change in changes {
+                                ui.label(change);
+                            }
+                        });
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Reboot now").clicked() {
+                        status.set_apply_and_reboot_confirmation(false);
+                        status.reboot();
+                    }
+                    if ui.button("Cancel").clicked() {
+                        status.set_apply_and_reboot_confirmation(false);
+                    }
+                });
+            });
+    }
+
+    fn power_confirmation_window(
+        ctx: &egui::Context,
+        status: &Status,
+        action: PowerAction,
+        deadline: Option<Instant>,
+    ) {
+        if let Some(deadline) = deadline {
+            if Instant::now() >= deadline {
+                status.set_power_confirmation(None);
+                match action {
+                    PowerAction::Reboot => status.reboot(),
+                    PowerAction::Shutdown => status.shutdown(),
+                }
+                return;
+            }
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+        egui::Window::new(action.label())
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                match deadline {
+                    Some(deadline) => ui.label(format!(
+                        "{} in {} s...",
+                        action.label(),
+                        deadline
+                            .saturating_duration_since(Instant::now())
+                            .as_secs_f32()
+                            .ceil()
+                    )),
+                    None => ui.label(format!(
+                        "{} now? Unsaved changes in other apps will be lost.",
+                        action.label()
+                    )),
+                };
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if deadline.is_none() && ui.button(action.label()).clicked() {
+                        status.start_power_countdown();
+                    }
+                    if ui.button("Cancel").clicked() {
+                        status.set_power_confirmation(None);
+                    }
+                });
+            });
+    }
+
+    fn reboot_fallback_window(ctx: &egui::Context, status: &Status) {
+        egui::Window::new("Reboot failed")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("The system could not be rebooted from the Editor.");
+                ui.label("Reboot it manually from the desktop session or with the command:");
+                ui.horizontal(|ui| {
+                    ui.code(REBOOT_COMMAND);
+                    if ui.small_button("📋").on_hover_text("Copy").clicked() {
+                        ui.output_mut(|output| output.copied_text = REBOOT_COMMAND.to_string());
+                    }
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Retry").clicked() {
+                        status.set_reboot_fallback(false);
+                        status.reboot();
+                    }
+                    if ui.button("Close").clicked() {
+                        status.set_reboot_fallback(false);
+                    }
+                });
+            });
+    }
+
     fn select_root_ui(&mut self, ui: &mut egui::Ui) {
         if let Application::SelectRoot { roots, status } = self {
             if roots.is_empty() {
                 ui.label("Firmware Attributes root not found");
+                if let Some(environment) = container_environment() {
+                    ui.label(format!(
+                        "The Editor seems to be running inside {}, where {} is usually absent \
+                        or not backed by the host firmware. Run it on the host system instead.",
+                        environment, PATH_SYSFS_FIRMWARE_ATTRIBUTES
+                    ));
+                }
             } else {
                 let mut selected: Option<&PathBuf> = None;
                 egui::ComboBox::from_id_source("Select Root")
@@ -417,6 +2965,7 @@ impl Application<Attribute> {
                         &format!("Root: {:?}", root),
                     );
                     if let Some(state) = state {
+                        status.set_last_root(root);
                         *self = state;
                     }
                 }
@@ -424,3 +2973,134 @@ impl Application<Attribute> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs_firmware_attributes::read_attributes;
+    use crate::test_root::TestRoot;
+
+    /// Fake root with string Attributes having the given properties
+    fn test_root(test: &str, attributes: &[(&str, &[(&str, &str)])]) -> TestRoot {
+        let root = TestRoot::new(test);
+        for (name, properties) in attributes {
+            root.attribute(name, &[("type", "string"), ("current_value", "")]);
+            root.attribute(name, properties);
+        }
+        root
+    }
+
+    fn controls(root: &TestRoot) -> Vec<Control<Attribute>> {
+        let attributes = read_attributes(&root.root).unwrap();
+        let access_mode = AccessMode::ReadOnly;
+        Application::controls(&root.root, attributes, &access_mode, &Status::default()).0
+    }
+
+    fn names<'a>(controls: &[&'a Control<Attribute>]) -> Vec<&'a str> {
+        controls
+            .iter()
+            .map(|control| control.attribute().name().as_str())
+            .collect()
+    }
+
+    #[test]
+    fn grouped_by_category_in_firmware_order() {
+        let root = test_root(
+            "grouped_by_category",
+            &[
+                ("AssetTag", &[]),
+                ("SecureBoot", &[("category", "Security"), ("order", "3")]),
+                ("BootMode", &[("category", "Boot"), ("order", "2")]),
+                ("TpmState", &[("category", "Security"), ("order", "1")]),
+            ],
+        );
+        let controls = controls(&root);
+        let controls: Vec<&Control<Attribute>> = controls.iter().collect();
+        assert_eq!(
+            names(&controls),
+            ["TpmState", "BootMode", "SecureBoot", "AssetTag"]
+        );
+        let groups = group_by_category(&controls);
+        let groups: Vec<(&str, Vec<&str>)> = groups
+            .iter()
+            .map(|(category, controls)| (*category, names(controls)))
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                ("Security", vec!["TpmState", "SecureBoot"]),
+                ("Boot", vec!["BootMode"]),
+                (UNCATEGORIZED, vec!["AssetTag"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn changed_type_redetected() {
+        let root = test_root(
+            "changed_type_redetected",
+            &[("AssetTag", &[]), ("Mode", &[("current_value", "Fast")])],
+        );
+        let mut controls = controls(&root);
+        let mode = root.root.join("attributes").join("Mode");
+        fs::write(mode.join("type"), "enumeration\n").unwrap();
+        fs::write(mode.join("possible_values"), "Fast;Slow\n").unwrap();
+        // A missing type file is not a change
+        fs::remove_file(root.root.join("attributes").join("AssetTag").join("type")).unwrap();
+        let changed: Vec<&str> = controls
+            .iter()
+            .filter(|control| control.attribute().is_type_changed())
+            .map(|control| control.attribute().name().as_str())
+            .collect();
+        assert_eq!(changed, ["Mode"]);
+
+        Application::redetect_types(&mut controls, &AccessMode::ReadOnly, &Status::default());
+        let mut types: Vec<(&str, &str)> = controls
+            .iter()
+            .map(|control| {
+                let attribute = control.attribute();
+                (attribute.name().as_str(), attribute.attribute_type())
+            })
+            .collect();
+        types.sort();
+        assert_eq!(types, [("AssetTag", "string"), ("Mode", "enumeration")]);
+        assert!(!controls
+            .iter()
+            .any(|control| control.attribute().is_type_changed()));
+    }
+
+    #[test]
+    fn undo_at_full_depth() {
+        let root = test_root(
+            "undo_at_full_depth",
+            &[("AssetTag", &[("current_value", "A")])],
+        );
+        let status = Status::new(Settings {
+            snapshot_directory: None,
+            undo_depth: 2,
+            ..Default::default()
+        });
+        let attributes = read_attributes(&root.root).unwrap();
+        let access_mode = AccessMode::ReadWrite;
+        let controls = Application::controls(&root.root, attributes, &access_mode, &status).0;
+        let control = &controls[0];
+        let string = |value: &str| AttributeValue::String(value.to_string());
+        for value in ["B", "C", "D"] {
+            assert!(control.write_confirmed(&string(value)));
+        }
+        let undo_values = |status: &Status| -> Vec<AttributeValue> {
+            let inner = status.inner.lock().unwrap();
+            inner
+                .undo_stack
+                .iter()
+                .map(|(_, value)| value.clone())
+                .collect()
+        };
+        assert_eq!(undo_values(&status), [string("B"), string("C")]);
+
+        let (_, value) = status.last_undo().unwrap();
+        Application::undo(&status, control, &value);
+        assert_eq!(control.attribute().current_value().unwrap(), value);
+        assert_eq!(undo_values(&status), [string("B")]);
+    }
+}
@@ -1,109 +1,549 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use crate::application::Status;
+use crate::application::{AccessMode, PendingWrite, Status};
 use crate::sysfs_firmware_attributes::{
-    Attribute, AttributeParser, ReadableAttribute, WriteableAttribute,
+    Attribute, AttributeError, AttributeParser, AttributeValue, EnumerationAttribute,
+    IntegerAttribute, ReadableAttribute, WriteableAttribute,
 };
-use egui::Widget;
+use egui::{RichText, Widget};
 use std::fmt::Debug;
 
+const SEARCHABLE_COMBOBOX_THRESHOLD: usize = 12;
+const RADIO_BUTTONS_THRESHOLD: usize = 3;
+const WRITE_ONCE_WARNING: &str = "One-time write — cannot be undone";
+
+/// Widget of an enumeration Attribute chosen by the number of its possible values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EnumerationWidget {
+    Label,
+    RadioButtons,
+    ComboBox,
+}
+
+fn enumeration_widget(possible_values_count: usize) -> EnumerationWidget {
+    match possible_values_count {
+        0 => EnumerationWidget::Label,
+        count if count <= RADIO_BUTTONS_THRESHOLD => EnumerationWidget::RadioButtons,
+        _ => EnumerationWidget::ComboBox,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Control<T: AttributeParser> {
     status: Status,
     attribute: T::Attr,
+    writable: bool,
+    lock_hint: &'static str,
 }
 
 impl Control<Attribute> {
-    pub fn new(attribute: Attribute, status: &Status) -> Self {
+    pub fn new(
+        mut attribute: Attribute,
+        status: &Status,
+        access_mode: &AccessMode<Attribute>,
+    ) -> Self {
+        attribute.set_display_language(status.display_language());
+        attribute.set_cache_ttl(status.cache_ttl());
+        let programmed = attribute.is_programmed();
+        let read_only = !attribute.is_writable();
         Self {
-            attribute,
             status: status.clone(),
+            writable: access_mode.write_access() && !programmed && !read_only,
+            lock_hint: if read_only {
+                "Attribute is read-only in the firmware"
+            } else if programmed {
+                "One-time write Attribute is already programmed"
+            } else if status.read_only() {
+                "Read-only mode is forced on the command line"
+            } else {
+                access_mode.lock_hint()
+            },
+            attribute,
         }
     }
 
-    fn current_value<T>(&self, attr: &dyn ReadableAttribute<Value = T>) -> Option<T> {
-        self.status.handle_result(attr.current_value())
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.attribute.write_once() {
+            warnings.push(WRITE_ONCE_WARNING.to_string());
+        }
+        warnings.extend(self.status.knowledge_base_warnings(self.attribute.name()));
+        warnings
+    }
+
+    pub fn set_display_language(&mut self, language: Option<String>) {
+        self.attribute.set_display_language(language);
+    }
+
+    pub fn attribute(&self) -> &Attribute {
+        &self.attribute
+    }
+
+    /// Display names are not unique, widget ids are derived from the internal name
+    fn id_source(&self) -> &str {
+        self.attribute.name()
+    }
+
+    fn writable(&self) -> bool {
+        self.writable && !self.status.view_only()
     }
 
-    fn write_current_value<T: Debug + PartialEq>(
+    /// The staged value when there is one, so the widgets show the planned state
+    fn current_value<T: TryFrom<AttributeValue>>(
+        &self,
+        attr: &dyn ReadableAttribute<Value = T>,
+    ) -> Option<T> {
+        self.status
+            .staged_value(self.attribute.name())
+            .and_then(|value| T::try_from(value).ok())
+            .or_else(|| self.status.handle_result(attr.current_value()))
+    }
+
+    fn write_current_value<T: Debug + PartialEq + Clone + Into<AttributeValue>>(
         &self,
         attr: &dyn WriteableAttribute<Value = T>,
         value: &T,
     ) {
-        if let Ok(current) = attr.current_value() {
-            if value == &current {
-                return;
+        if self.status.view_only() {
+            return;
+        }
+        let unchanged = attr.current_value().ok().as_ref() == Some(value);
+        if self.status.staging() {
+            // Warnings are confirmed for all staged values on apply
+            self.status.stage(
+                self.attribute.name(),
+                (!unchanged).then(|| value.clone().into()),
+            );
+            return;
+        }
+        if unchanged {
+            return;
+        }
+        let warnings = self.warnings();
+        if warnings.is_empty() {
+            self.write_confirmed_value(attr, value);
+        } else {
+            self.status.request_confirmation(PendingWrite {
+                name: self.attribute.name().clone(),
+                display_name: self.attribute.display_name().clone(),
+                value: value.clone().into(),
+                warnings,
+            });
+        }
+    }
+
+    fn can_reset_to_default(&self) -> bool {
+        match self.attribute.default_value() {
+            Some(default_value) => self.attribute.current_value().ok() != Some(default_value),
+            None => false,
+        }
+    }
+
+    fn reset_to_default(&self) {
+        match &self.attribute {
+            Attribute::Enumeration(attr) => self.write_default_value(attr),
+            Attribute::Integer(attr) => self.write_default_value(attr),
+            Attribute::String(attr) => self.write_default_value(attr),
+            Attribute::OrderedList(attr) => self.write_default_value(attr),
+            Attribute::EnumerationList(attr) => self.write_default_value(attr),
+            Attribute::Map(attr) => self.write_default_value(attr),
+            Attribute::Raw(attr) => self.write_default_value(attr),
+        }
+    }
+
+    /// Lists are joined with the delimiters detected for the Attribute on write
+    fn write_default_value<T: Debug + PartialEq + Clone + Into<AttributeValue>>(
+        &self,
+        attr: &dyn WriteableAttribute<Value = T>,
+    ) {
+        if let Some(default_value) = &attr.common_attribute().default_value {
+            self.write_current_value(attr, default_value);
+        }
+    }
+
+    /// Values between the steps, for example unaligned values of the firmware, are rounded
+    fn write_integer_value(&self, attr: &IntegerAttribute, value: i64) {
+        let rounded = attr.round_to_increment(value);
+        self.write_current_value(attr, &rounded);
+        if rounded != value && !self.status.has_error() {
+            self.status.show_message(format!(
+                "{} is not a valid value of Attribute {:?}, rounded to {} (range {}..={}, step {})",
+                value,
+                self.attribute.display_name(),
+                rounded,
+                attr.min_value,
+                attr.max_value,
+                attr.scalar_increment
+            ));
+        }
+    }
+
+    /// Firmware can report a current value that is not possible to write back
+    fn write_enumeration_value(&self, attr: &EnumerationAttribute, value: &String) {
+        if attr.is_possible_value(value) {
+            self.write_current_value(attr, value);
+        } else {
+            self.status
+                .handle_result::<()>(Err(AttributeError::InvalidValue(format!(
+                    "{:?} is not a possible value of Attribute {:?}",
+                    value,
+                    self.attribute.display_name()
+                ))));
+        }
+    }
+
+    /// Writes the default value without asking for confirmation of the warnings,
+    /// `None` when there is nothing to reset
+    pub fn reset_to_default_confirmed(&self) -> Option<bool> {
+        if !self.writable() || !self.can_reset_to_default() {
+            return None;
+        }
+        let default_value = self.attribute.default_value()?;
+        Some(self.write_confirmed(&default_value))
+    }
+
+    /// Writes the value after the user confirmed the warnings of the pending write
+    pub fn write_confirmed(&self, value: &AttributeValue) -> bool {
+        match (&self.attribute, value) {
+            (Attribute::Enumeration(attr), AttributeValue::String(value)) => {
+                self.write_confirmed_value(attr, value)
+            }
+            (Attribute::Integer(attr), AttributeValue::Integer(value)) => {
+                self.write_confirmed_value(attr, value)
+            }
+            (Attribute::String(attr), AttributeValue::String(value)) => {
+                self.write_confirmed_value(attr, value)
             }
+            (Attribute::OrderedList(attr), AttributeValue::List(value)) => {
+                self.write_confirmed_value(attr, value)
+            }
+            (Attribute::EnumerationList(attr), AttributeValue::List(value)) => {
+                self.write_confirmed_value(attr, value)
+            }
+            (Attribute::Map(attr), AttributeValue::Map(value)) => {
+                self.write_confirmed_value(attr, value)
+            }
+            (Attribute::Raw(attr), AttributeValue::String(value)) => {
+                self.write_confirmed_value(attr, value)
+            }
+            (_, value) => self
+                .status
+                .handle_result(self.attribute.write_value(value))
+                .is_some(),
+        }
+    }
+
+    fn write_confirmed_value<T: Debug + PartialEq + Clone + Into<AttributeValue>>(
+        &self,
+        attr: &dyn WriteableAttribute<Value = T>,
+        value: &T,
+    ) -> bool {
+        let current = attr.current_value().ok();
+        let name = attr.common_attribute().display_name();
+        if let Some(root) = attr.common_attribute().root() {
+            self.status.take_snapshot(root);
+        }
+        let result = attr.write_current_value(value);
+        if let Err(err) = &result {
+            self.status.record_write_failure(name, err);
+        }
+        if self
+            .status
+            .handle_result_with_message(
+                result,
+                &format!("Value updated for Attribute {:?} to {:?}", name, value),
+            )
+            .is_some()
+        {
+            self.status
+                .record_modification(&attr.common_attribute().path);
+            if let Some(root) = attr.common_attribute().root() {
+                self.status
+                    .write_companions(root, &attr.common_attribute().name);
+            }
+            if let Some(current) = &current {
+                self.status
+                    .record_undo(&attr.common_attribute().name, current.clone().into());
+            }
+            self.status.record_change(match current {
+                Some(current) => format!("{}: {:?} → {:?}", name, current, value),
+                None => format!("{}: {:?}", name, value),
+            });
+            true
+        } else {
+            false
         }
-        self.status.handle_result_with_message(
-            attr.write_current_value(value),
-            &format!(
-                "Value updated for Attribute {:?} to {:?}",
-                attr.common_attribute().display_name(),
-                value
+    }
+
+    fn tooltip(&self) -> String {
+        let mut lines = vec![format!("Type: {}", self.attribute.type_description())];
+        for warning in self.warnings() {
+            lines.push(format!("⚠ {}", warning));
+        }
+        if let Some(timestamp) = self.status.last_modified(self.attribute.path()) {
+            lines.push(format!(
+                "Last modified by this tool: {}",
+                timestamp.format("%d/%m/%Y %H:%M:%S")
+            ));
+        }
+        lines.join("\n")
+    }
+
+    /// Constraints of the value, shown on hover of the value widget
+    fn constraints_hint(&self) -> String {
+        let default_value = self
+            .attribute
+            .default_value()
+            .map_or("-".to_string(), |value| self.attribute.format_value(&value));
+        match &self.attribute {
+            Attribute::Integer(attr) => format!(
+                "Range: {}..={}, step: {}, default: {}",
+                attr.min_value, attr.max_value, attr.scalar_increment, default_value
             ),
-        );
+            Attribute::String(attr) => format!(
+                "Length: {}..={}, default: {}",
+                attr.min_length, attr.max_length, default_value
+            ),
+            Attribute::Enumeration(attr) => format!(
+                "{} options, default: {}",
+                attr.possible_values_count(),
+                default_value
+            ),
+            _ => format!("Default: {}", default_value),
+        }
     }
-}
 
-impl Widget for Control<Attribute> {
-    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+    pub fn details_ui(&self, ui: &mut egui::Ui) {
+        let attribute = &self.attribute;
+        let format_value = |value: Option<AttributeValue>| {
+            value.map_or("-".to_string(), |value| attribute.format_value(&value))
+        };
+        egui::Grid::new("Attribute Details")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                let mut row = |name: &str, value: String| {
+                    ui.strong(name);
+                    ui.label(value);
+                    ui.end_row();
+                };
+                row("Name", attribute.name().clone());
+                row("Display name", attribute.display_name().clone());
+                row("Type", attribute.attribute_type().to_string());
+                row("Raw type", attribute.raw_type().clone());
+                if !attribute.is_writable() {
+                    row("Read only", "Read-only in the firmware".to_string());
+                }
+                if attribute.write_once() {
+                    row(
+                        "Write once",
+                        if attribute.is_programmed() {
+                            "Already programmed".to_string()
+                        } else {
+                            "Not programmed yet".to_string()
+                        },
+                    );
+                }
+                row(
+                    "Requires reboot",
+                    attribute
+                        .requires_reboot()
+                        .map_or("Unknown".to_string(), |value| value.to_string()),
+                );
+                row(
+                    "Current value",
+                    format_value(attribute.current_value().ok()),
+                );
+                row("Default value", format_value(attribute.default_value()));
+                match attribute {
+                    Attribute::Enumeration(attr) => {
+                        row("Possible values", attr.possible_values().join("\n"));
+                    }
+                    Attribute::Integer(attr) => {
+                        row("Min value", attr.min_value.to_string());
+                        row("Max value", attr.max_value.to_string());
+                        row("Scalar increment", attr.scalar_increment.to_string());
+                    }
+                    Attribute::String(attr) => {
+                        row("Min length", attr.min_length.to_string());
+                        row("Max length", attr.max_length.to_string());
+                        row("Hint", attr.hint.clone().unwrap_or_default());
+                    }
+                    Attribute::OrderedList(attr) => {
+                        row("Elements", attr.elements.join("\n"));
+                        row("Value delimiter", format!("{:?}", attr.value_delimiter));
+                        row(
+                            "Elements delimiter",
+                            format!("{:?}", attr.elements_delimiter),
+                        );
+                    }
+                    Attribute::EnumerationList(attr) => {
+                        row("Possible values", attr.possible_values.join("\n"));
+                        row("Value delimiter", format!("{:?}", attr.value_delimiter));
+                        row(
+                            "Possible values delimiter",
+                            format!("{:?}", attr.possible_values_delimiter),
+                        );
+                    }
+                    Attribute::Map(attr) => {
+                        row("Trailing delimiter", attr.trailing_delimiter.to_string());
+                    }
+                    Attribute::Raw(_) => {}
+                }
+                let warnings = self.warnings();
+                if !warnings.is_empty() {
+                    row("Warnings", warnings.join("\n"));
+                }
+                row("Path", attribute.path().display().to_string());
+                row(
+                    "Last modified by this tool",
+                    self.status
+                        .last_modified(attribute.path())
+                        .map_or("-".to_string(), |timestamp| {
+                            timestamp.format("%d/%m/%Y %H:%M:%S").to_string()
+                        }),
+                );
+            });
+    }
+
+    fn value_ui(&self, ui: &mut egui::Ui) -> bool {
         let mut changed = false;
         match &self.attribute {
             Attribute::Enumeration(attr) => {
-                if let Some(mut current_value) = self.current_value(attr) {
-                    let name = attr.common_attribute().display_name();
-                    if ui
-                        .add(enumeration_combobox(
-                            name,
-                            &mut current_value,
-                            &attr.possible_values,
-                        ))
-                        .changed()
-                    {
-                        changed = true;
-                        self.write_current_value(attr, &current_value);
+                match enumeration_widget(attr.possible_values_count()) {
+                    EnumerationWidget::Label => {
+                        if let Some(current_value) = self.current_value(attr) {
+                            ui.horizontal(|ui| {
+                                ui.label(current_value);
+                                ui.weak("(no options exposed by the firmware)");
+                            });
+                        }
+                    }
+                    EnumerationWidget::RadioButtons => {
+                        if let Some(mut current_value) = self.current_value(attr) {
+                            let in_list = attr.is_possible_value(&current_value);
+                            if ui
+                                .add(enumeration_radio_buttons(
+                                    &mut current_value,
+                                    attr.possible_values(),
+                                    in_list,
+                                ))
+                                .on_hover_text(self.constraints_hint())
+                                .changed()
+                            {
+                                changed = true;
+                                self.write_enumeration_value(attr, &current_value);
+                            }
+                        }
+                    }
+                    EnumerationWidget::ComboBox => {
+                        if let Some(mut current_value) = self.current_value(attr) {
+                            let id_source = self.id_source();
+                            let in_list = attr.is_possible_value(&current_value);
+                            if ui
+                                .add(enumeration_combobox(
+                                    id_source,
+                                    &mut current_value,
+                                    || attr.possible_values(),
+                                    in_list,
+                                ))
+                                .on_hover_text(self.constraints_hint())
+                                .changed()
+                            {
+                                changed = true;
+                                self.write_enumeration_value(attr, &current_value);
+                            }
+                        }
                     }
                 }
             }
-            Attribute::Integer(attr) => {
+            Attribute::Integer(attr) if self.status.is_size(self.attribute.name()) => {
                 if let Some(mut current_value) = self.current_value(attr) {
-                    let name = attr.common_attribute().display_name();
                     if ui
-                        .add(integer_input(
-                            name,
+                        .add(size_input(
                             &mut current_value,
                             attr.min_value,
                             attr.max_value,
-                            attr.scalar_increment,
                         ))
                         .changed()
                     {
                         changed = true;
-                        self.write_current_value(attr, &current_value);
+                        self.write_integer_value(attr, current_value);
                     }
                 }
             }
+            Attribute::Integer(attr) => {
+                if let Some(mut current_value) = self.current_value(attr) {
+                    let hex_id = egui::Id::new(("Hex Input", self.id_source()));
+                    let mut hex: bool = ui.data(|data| data.get_temp(hex_id)).unwrap_or(false);
+                    ui.horizontal(|ui| {
+                        if ui
+                            .selectable_label(hex, "0x")
+                            .on_hover_text("Hexadecimal input")
+                            .clicked()
+                        {
+                            hex = !hex;
+                            ui.data_mut(|data| data.insert_temp(hex_id, hex));
+                        }
+                        if hex {
+                            let mut parsed = None;
+                            ui.add(hex_input(&mut parsed, current_value));
+                            match parsed {
+                                Some(Ok(value)) if value != current_value => {
+                                    changed = true;
+                                    self.write_integer_value(attr, value);
+                                }
+                                Some(Err(err)) => {
+                                    self.status.handle_result::<()>(Err(err));
+                                }
+                                _ => {}
+                            }
+                        } else if ui
+                            .add(integer_input(
+                                &mut current_value,
+                                attr.min_value,
+                                attr.max_value,
+                                attr.scalar_increment,
+                            ))
+                            .on_hover_text(self.constraints_hint())
+                            .changed()
+                        {
+                            changed = true;
+                            self.write_integer_value(attr, current_value);
+                        }
+                    });
+                }
+            }
             Attribute::String(attr) => {
                 if let Some(current_value) = self.current_value(attr) {
                     let id = ui.id();
                     let mut current_value = ui
                         .memory(|mem| mem.data.get_temp(id))
                         .unwrap_or(current_value);
-                    let name = attr.common_attribute().display_name();
-                    let input_response = ui.add(string_input(
-                        name,
-                        &mut current_value,
-                        attr.min_length,
-                        attr.max_length,
-                        attr.hint.as_ref().unwrap_or(&"".to_string()),
-                    ));
+                    let input_response = ui
+                        .add(string_input(
+                            &mut current_value,
+                            attr.min_length,
+                            attr.max_length,
+                            attr.hint.as_ref().unwrap_or(&"".to_string()),
+                        ))
+                        .on_hover_text(self.constraints_hint());
                     if input_response.lost_focus()
                         || (input_response.has_focus()
                             && ui.input(|i| i.key_pressed(egui::Key::Enter)))
                     {
-                        changed = true;
-                        self.write_current_value(attr, &current_value);
+                        if is_string_length_valid(&current_value, attr.min_length, attr.max_length)
+                        {
+                            changed = true;
+                            self.write_current_value(attr, &current_value);
+                        } else {
+                            self.status
+                                .handle_result::<()>(Err(AttributeError::InvalidValue(format!(
+                                    "Value too short (min {}) for Attribute {:?}",
+                                    attr.min_length,
+                                    self.attribute.display_name()
+                                ))));
+                        }
                         ui.memory_mut(|mem| mem.data.remove::<String>(id));
                     } else if input_response.has_focus() {
                         ui.memory_mut(|mem| mem.data.insert_temp(id, current_value));
@@ -112,12 +552,13 @@ impl Widget for Control<Attribute> {
             }
             Attribute::OrderedList(attr) => {
                 if let Some(mut current_value) = self.current_value(attr) {
-                    let name = attr.common_attribute().display_name();
+                    let id_source = self.id_source();
                     if ui
                         .add(ordered_list_widget(
-                            name,
+                            id_source,
                             &mut current_value,
                             &attr.elements,
+                            self.status.ordered_list_duplicates(),
                         ))
                         .changed()
                     {
@@ -128,12 +569,13 @@ impl Widget for Control<Attribute> {
             }
             Attribute::EnumerationList(attr) => {
                 if let Some(mut current_value) = self.current_value(attr) {
-                    let name = attr.common_attribute().display_name();
+                    let id_source = self.id_source();
                     if ui
                         .add(ordered_list_widget(
-                            name,
+                            id_source,
                             &mut current_value,
                             &attr.possible_values,
+                            false,
                         ))
                         .changed()
                     {
@@ -142,9 +584,141 @@ impl Widget for Control<Attribute> {
                     }
                 }
             }
+            Attribute::Map(attr) => {
+                if let Some(current_value) = self.current_value(attr) {
+                    let id = ui.id();
+                    let mut current_value = ui
+                        .memory(|mem| mem.data.get_temp(id))
+                        .unwrap_or(current_value);
+                    let response = ui.add(map_widget(&mut current_value));
+                    if response.lost_focus()
+                        || (response.has_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)))
+                    {
+                        changed = true;
+                        self.write_current_value(attr, &current_value);
+                        ui.memory_mut(|mem| mem.data.remove::<Vec<(String, String)>>(id));
+                    } else if response.has_focus() {
+                        ui.memory_mut(|mem| mem.data.insert_temp(id, current_value));
+                    }
+                }
+            }
+            Attribute::Raw(attr) => {
+                if let Some(current_value) = self.current_value(attr) {
+                    let id = ui.id();
+                    let mut current_value = ui
+                        .memory(|mem| mem.data.get_temp(id))
+                        .unwrap_or(current_value);
+                    ui.horizontal(|ui| {
+                        let response = ui.text_edit_singleline(&mut current_value);
+                        ui.weak(format!("type: {}", self.attribute.raw_type()))
+                            .on_hover_text("Unknown type, the value is written as is");
+                        if response.lost_focus()
+                            || (response.has_focus()
+                                && ui.input(|i| i.key_pressed(egui::Key::Enter)))
+                        {
+                            changed = true;
+                            self.write_current_value(attr, &current_value);
+                            ui.memory_mut(|mem| mem.data.remove::<String>(id));
+                        } else if response.has_focus() {
+                            ui.memory_mut(|mem| mem.data.insert_temp(id, current_value));
+                        }
+                    });
+                }
+            }
         };
-        let mut response = ui.label("");
-        if changed {
+        changed
+    }
+}
+
+impl Widget for Control<Attribute> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        ui.horizontal(|ui| {
+            if self.writable() {
+                ui.weak("🔓").on_hover_text(self.lock_hint);
+            } else if self.writable {
+                ui.weak("🔒").on_hover_text("View only mode is enabled");
+            } else {
+                ui.weak("🔒").on_hover_text(self.lock_hint);
+            }
+            ui.label(self.attribute.display_name())
+                .on_hover_text(self.tooltip());
+            if self.status.staged_value(self.attribute.name()).is_some() {
+                ui.label(RichText::new("●").color(ui.visuals().warn_fg_color))
+                    .on_hover_text("Staged, written on apply");
+            }
+            if self.attribute.write_once() {
+                ui.label(RichText::new("⚠ One-time").color(ui.visuals().error_fg_color))
+                    .on_hover_text(WRITE_ONCE_WARNING);
+            }
+            let path = self.attribute.path().display().to_string();
+            if ui
+                .small_button("ℹ")
+                .on_hover_text(format!(
+                    "{}\nType: {}\nDefault value: {}\nClick to copy the path",
+                    path,
+                    self.attribute.raw_type(),
+                    if self.attribute.default_value().is_some() {
+                        "present"
+                    } else {
+                        "absent"
+                    }
+                ))
+                .clicked()
+            {
+                self.status
+                    .show_message(format!("Path copied to the clipboard: {}", path));
+                ui.output_mut(|o| o.copied_text = path);
+            }
+        });
+        // Ids derived from the attribute keep the focus when the controls are rebuilt
+        let changed = ui
+            .push_id(self.id_source(), |ui| {
+                ui.set_enabled(self.writable());
+                self.value_ui(ui)
+            })
+            .inner;
+        let mut changed_to_default = false;
+        let mut response = ui
+            .horizontal(|ui| {
+                let reset = ui
+                    .add_enabled(
+                        self.writable() && self.can_reset_to_default(),
+                        egui::Button::new("↺").small(),
+                    )
+                    .on_hover_text("Reset to default")
+                    .on_disabled_hover_text("No default value or the value is already default");
+                if reset.clicked() {
+                    changed_to_default = true;
+                    self.reset_to_default();
+                }
+                if let Some(value) = self.status.external_change(self.attribute.path()) {
+                    ui.label(
+                        RichText::new("⚠ Changed externally")
+                            .color(ui.visuals().warn_fg_color)
+                            .background_color(ui.visuals().faint_bg_color),
+                    )
+                    .on_hover_text(format!("The firmware reports {:?} now", value));
+                    if ui
+                        .small_button("Accept external change")
+                        .on_hover_text("Show the value reported by the firmware")
+                        .clicked()
+                    {
+                        self.attribute.clear_current_value_cache();
+                        self.status.accept_external_change(self.attribute.path());
+                    }
+                }
+                match self.attribute.requires_reboot() {
+                    Some(true) => ui
+                        .label("↻")
+                        .on_hover_text("Changes are applied after reboot"),
+                    Some(false) => ui
+                        .weak("⚡")
+                        .on_hover_text("Changes are applied immediately"),
+                    None => ui.label(""),
+                };
+            })
+            .response;
+        if changed || changed_to_default {
             response.mark_changed();
         }
         response
@@ -152,19 +726,61 @@ impl Widget for Control<Attribute> {
 }
 
 fn enumeration_combobox<'a>(
-    name: &'a str,
+    id_source: &'a str,
     current_value: &'a mut String,
-    possible_values: &'a Vec<String>,
+    possible_values: impl Fn() -> &'a Vec<String> + 'a,
+    in_list: bool,
 ) -> impl Widget + 'a {
     move |ui: &mut egui::Ui| -> egui::Response {
         let before = current_value.clone();
-        ui.label(name);
-        let mut response = egui::ComboBox::from_id_source(name)
-            .selected_text(current_value.as_str())
+        let filter_id = ui.make_persistent_id(id_source).with("filter");
+        let selected_text = if in_list {
+            current_value.clone()
+        } else {
+            not_in_list(current_value)
+        };
+        let inner_response = egui::ComboBox::from_id_source(id_source)
+            .selected_text(selected_text.as_str())
             .show_ui(ui, |ui| {
-                for variant in possible_values {
+                if !in_list {
+                    ui.add_enabled(
+                        false,
+                        egui::SelectableLabel::new(true, selected_text.as_str()),
+                    );
+                }
+                // Values are prepared only while the popup is open
+                let possible_values = display_order(possible_values());
+                for variant in searchable_values(ui, filter_id, &possible_values) {
                     ui.selectable_value(current_value, variant.clone(), variant);
                 }
+            });
+        clear_filter_if_closed(ui, filter_id, &inner_response);
+        let mut response = inner_response.response;
+        if before != *current_value {
+            response.mark_changed();
+        }
+        response
+    }
+}
+
+fn enumeration_radio_buttons<'a>(
+    current_value: &'a mut String,
+    possible_values: &'a [String],
+    in_list: bool,
+) -> impl Widget + 'a {
+    move |ui: &mut egui::Ui| -> egui::Response {
+        let before = current_value.clone();
+        let mut response = ui
+            .horizontal(|ui| {
+                if !in_list {
+                    ui.add_enabled(
+                        false,
+                        egui::RadioButton::new(true, not_in_list(current_value)),
+                    );
+                }
+                for variant in display_order(possible_values) {
+                    ui.radio_value(current_value, variant.clone(), variant);
+                }
             })
             .response;
         if before != *current_value {
@@ -174,15 +790,29 @@ fn enumeration_combobox<'a>(
     }
 }
 
+fn not_in_list(value: &str) -> String {
+    format!("{} (current, not in list)", value)
+}
+
+/// Numeric enumerations are sorted by value, other keep the firmware order
+fn display_order(possible_values: &[String]) -> Vec<String> {
+    let mut values = possible_values.to_vec();
+    if values
+        .iter()
+        .all(|value| value.trim().parse::<i64>().is_ok())
+    {
+        values.sort_by_key(|value| value.trim().parse::<i64>().unwrap_or_default());
+    }
+    values
+}
+
 fn integer_input<'a>(
-    name: &'a str,
-    current_value: &'a mut i32,
-    min: i32,
-    max: i32,
-    step: i32,
+    current_value: &'a mut i64,
+    min: i64,
+    max: i64,
+    step: i64,
 ) -> impl Widget + 'a {
     move |ui: &mut egui::Ui| -> egui::Response {
-        ui.label(name);
         egui::Slider::new(current_value, min..=max)
             .step_by(step as f64)
             .clamp_to_range(true)
@@ -190,63 +820,205 @@ fn integer_input<'a>(
     }
 }
 
+/// Sets `parsed` when the edit is committed
+fn hex_input<'a>(
+    parsed: &'a mut Option<Result<i64, AttributeError>>,
+    current_value: i64,
+) -> impl Widget + 'a {
+    move |ui: &mut egui::Ui| -> egui::Response {
+        let id = ui.id().with("hex");
+        let mut text: String = ui
+            .memory(|mem| mem.data.get_temp(id))
+            .unwrap_or_else(|| format!("{:#x}", current_value));
+        let response = egui::TextEdit::singleline(&mut text)
+            .hint_text("0x0")
+            .desired_width(120.0)
+            .ui(ui)
+            .on_hover_text(format!("{} in decimal", current_value));
+        if response.lost_focus() {
+            ui.memory_mut(|mem| mem.data.remove::<String>(id));
+            let digits = text.trim();
+            let digits = digits
+                .strip_prefix("0x")
+                .or_else(|| digits.strip_prefix("0X"))
+                .unwrap_or(digits);
+            *parsed = Some(i64::from_str_radix(digits, 16).map_err(AttributeError::from));
+        } else if response.has_focus() {
+            ui.memory_mut(|mem| mem.data.insert_temp(id, text));
+        }
+        response
+    }
+}
+
+fn size_input<'a>(current_value: &'a mut i64, min: i64, max: i64) -> impl Widget + 'a {
+    move |ui: &mut egui::Ui| -> egui::Response {
+        let id = ui.id().with("size");
+        let mut text: String = ui
+            .memory(|mem| mem.data.get_temp(id))
+            .unwrap_or_else(|| format_size(*current_value));
+        let mut response = egui::TextEdit::singleline(&mut text)
+            .hint_text("4G")
+            .ui(ui)
+            .on_hover_text(format!("{} bytes", current_value));
+        if response.lost_focus() {
+            ui.memory_mut(|mem| mem.data.remove::<String>(id));
+            let value = parse_size(&text).filter(|value| (min..=max).contains(value));
+            match value {
+                Some(value) if value != *current_value => {
+                    *current_value = value;
+                    response.mark_changed();
+                }
+                _ => {}
+            }
+        } else if response.has_focus() {
+            ui.memory_mut(|mem| mem.data.insert_temp(id, text));
+        }
+        response
+    }
+}
+
+const SIZE_UNITS: [&str; 5] = ["", "K", "M", "G", "T"];
+
+/// Formats with the largest binary unit that represents the value exactly
+fn format_size(value: i64) -> String {
+    let mut value = value;
+    let mut unit = 0;
+    while value != 0 && value % 1024 == 0 && unit < SIZE_UNITS.len() - 1 {
+        value /= 1024;
+        unit += 1;
+    }
+    match unit {
+        0 => value.to_string(),
+        _ => format!("{} {}iB", value, SIZE_UNITS[unit]),
+    }
+}
+
+/// Accepts "4096", "4K", "4 KiB" and "4KB", units are always binary
+fn parse_size(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let digits = text
+        .find(|c: char| !c.is_ascii_digit() && c != '-')
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(digits);
+    let number: i64 = number.parse().ok()?;
+    let unit = unit.trim().to_uppercase();
+    let unit = unit
+        .strip_suffix("IB")
+        .or_else(|| unit.strip_suffix('B'))
+        .unwrap_or(&unit);
+    let exponent = SIZE_UNITS.iter().position(|u| *u == unit)?;
+    number.checked_mul(1024i64.checked_pow(exponent as u32)?)
+}
+
 fn string_input<'a>(
-    name: &'a str,
     current_value: &'a mut String,
-    _min_length: usize,
+    min_length: usize,
     max_length: usize,
     hint: &'a str,
 ) -> impl Widget + 'a {
     move |ui: &mut egui::Ui| -> egui::Response {
-        ui.label(name);
-        let response = egui::TextEdit::singleline(current_value)
-            .char_limit(max_length)
-            .ui(ui);
-        ui.weak(hint);
+        let invalid = !is_string_length_valid(current_value, min_length, max_length);
+        let response = ui
+            .scope(|ui| {
+                if invalid {
+                    let stroke = egui::Stroke::new(1.0, ui.visuals().error_fg_color);
+                    let visuals = ui.visuals_mut();
+                    visuals.selection.stroke = stroke;
+                    visuals.widgets.inactive.bg_stroke = stroke;
+                    visuals.widgets.hovered.bg_stroke = stroke;
+                }
+                egui::TextEdit::singleline(current_value)
+                    .char_limit(max_length)
+                    .ui(ui)
+            })
+            .inner;
+        if invalid {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                format!("min {} characters", min_length),
+            );
+        } else {
+            ui.weak(hint);
+        }
         response
     }
 }
 
+/// Trailing and leading spaces don't count, firmware usually strips them
+fn is_string_length_valid(value: &str, min_length: usize, max_length: usize) -> bool {
+    (min_length..=max_length).contains(&value.trim().chars().count())
+}
+
+/// Table of keys and editable values, the response has focus while any value is edited
+fn map_widget(current_value: &mut [(String, String)]) -> impl Widget + '_ {
+    move |ui: &mut egui::Ui| -> egui::Response {
+        let mut response: Option<egui::Response> = None;
+        egui::Grid::new("Map").num_columns(2).show(ui, |ui| {
+            for (key, value) in current_value.iter_mut() {
+                ui.label(key.as_str());
+                let value_response = ui.text_edit_singleline(value);
+                ui.end_row();
+                // Prefer the response of the edited entry
+                if response.is_none() || value_response.has_focus() || value_response.lost_focus() {
+                    response = Some(value_response);
+                }
+            }
+        });
+        response.unwrap_or_else(|| ui.weak("(empty)"))
+    }
+}
+
 fn ordered_list_widget<'a>(
-    name: &'a str,
+    id_source: &'a str,
     current_value: &'a mut Vec<String>,
     possible_values: &'a Vec<String>,
+    allow_duplicates: bool,
 ) -> impl Widget + 'a {
     move |ui: &mut egui::Ui| -> egui::Response {
         let before = current_value.clone();
-        ui.label(name);
         let mut response = ui
             .vertical(|ui| {
                 let len = current_value.len();
                 for (index, value) in current_value.clone().iter().enumerate() {
                     ui.horizontal_top(|ui| {
-                        if ui.small_button("⬆").clicked() {
-                            if index == 0 {
-                                current_value.swap(index, len - 1);
-                            } else {
-                                current_value.swap(index, index - 1);
-                            }
+                        if ui
+                            .add_enabled(index > 0, egui::Button::new("⬆").small())
+                            .clicked()
+                        {
+                            move_up(current_value, index);
                         }
-                        if ui.small_button("⬇").clicked() {
-                            if index == len - 1 {
-                                current_value.swap(index, 0);
-                            } else {
-                                current_value.swap(index, index + 1);
-                            }
+                        if ui
+                            .add_enabled(index + 1 < len, egui::Button::new("⬇").small())
+                            .clicked()
+                        {
+                            move_down(current_value, index);
                         }
                         if ui.small_button("❌").clicked() {
                             current_value.remove(index);
                         }
-                        ui.label(value.as_str());
+                        if !is_orphan(possible_values, value) {
+                            ui.label(value.as_str());
+                        } else {
+                            ui.label(
+                                egui::RichText::new(format!("⚠ {}", value))
+                                    .color(ui.visuals().warn_fg_color),
+                            )
+                            .on_hover_text(
+                                "Not among the values exposed by the firmware. \
+                                It can be removed, but not added back.",
+                            );
+                        }
                     });
                 }
-                if !possible_values.is_empty() {
+                let available = addable_values(possible_values, current_value, allow_duplicates);
+                if !available.is_empty() {
                     ui.separator();
                     let mut selected: Option<&String> = None;
-                    egui::ComboBox::from_id_source(name)
+                    let filter_id = ui.make_persistent_id(id_source).with("filter");
+                    let inner_response = egui::ComboBox::from_id_source(id_source)
                         .selected_text("Add to list")
                         .show_ui(ui, |ui| {
-                            for possible_value in possible_values {
+                            for possible_value in searchable_values(ui, filter_id, &available) {
                                 ui.selectable_value(
                                     &mut selected,
                                     Some(possible_value),
@@ -254,6 +1026,7 @@ fn ordered_list_widget<'a>(
                                 );
                             }
                         });
+                    clear_filter_if_closed(ui, filter_id, &inner_response);
                     if let Some(selected) = selected {
                         current_value.push(selected.clone());
                     }
@@ -266,3 +1039,194 @@ fn ordered_list_widget<'a>(
         response
     }
 }
+
+/// Entry of the current value missing from the values exposed by the firmware
+fn is_orphan(possible_values: &[String], value: &String) -> bool {
+    !possible_values.is_empty() && !possible_values.contains(value)
+}
+
+/// Firmware usually rejects duplicates, the add control is hidden when all are used.
+/// Orphans are never offered, once removed they can't be added back
+fn addable_values(
+    possible_values: &[String],
+    current_value: &[String],
+    allow_duplicates: bool,
+) -> Vec<String> {
+    possible_values
+        .iter()
+        .filter(|value| allow_duplicates || !current_value.contains(value))
+        .cloned()
+        .collect()
+}
+
+/// No-op for the first entry, boot orders must not wrap around
+fn move_up(list: &mut [String], index: usize) {
+    if index > 0 && index < list.len() {
+        list.swap(index, index - 1);
+    }
+}
+
+/// No-op for the last entry, boot orders must not wrap around
+fn move_down(list: &mut [String], index: usize) {
+    if index + 1 < list.len() {
+        list.swap(index, index + 1);
+    }
+}
+
+fn searchable_values<'a>(
+    ui: &mut egui::Ui,
+    filter_id: egui::Id,
+    possible_values: &'a [String],
+) -> Vec<&'a String> {
+    if possible_values.len() <= SEARCHABLE_COMBOBOX_THRESHOLD {
+        return possible_values.iter().collect();
+    }
+    let mut filter: String = ui
+        .memory(|mem| mem.data.get_temp(filter_id))
+        .unwrap_or_default();
+    ui.add(egui::TextEdit::singleline(&mut filter).hint_text("Search"))
+        .request_focus();
+    let lowercase_filter = filter.to_lowercase();
+    ui.memory_mut(|mem| mem.data.insert_temp(filter_id, filter));
+    possible_values
+        .iter()
+        .filter(|value| value.to_lowercase().contains(&lowercase_filter))
+        .collect()
+}
+
+fn clear_filter_if_closed<R>(
+    ui: &mut egui::Ui,
+    filter_id: egui::Id,
+    inner_response: &egui::InnerResponse<Option<R>>,
+) {
+    if inner_response.inner.is_none() {
+        ui.memory_mut(|mem| mem.data.remove::<String>(filter_id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_root::TestRoot;
+
+    fn list(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn orphan_can_be_removed_but_not_added_back() {
+        let elements = list(&["a", "b", "c"]);
+        let mut current_value = list(&["b", "x", "a"]);
+        assert!(is_orphan(&elements, &current_value[1]));
+        assert!(!is_orphan(&elements, &current_value[0]));
+        current_value.remove(1);
+        assert_eq!(addable_values(&elements, &current_value, false), ["c"]);
+        assert_eq!(addable_values(&elements, &current_value, true), elements);
+    }
+
+    #[test]
+    fn numeric_enumeration_sorted_by_value() {
+        let values = list(&["256", "512", "1024", "128"]);
+        assert_eq!(display_order(&values), ["128", "256", "512", "1024"]);
+        assert_eq!(values, ["256", "512", "1024", "128"]);
+        let names = list(&["Disabled", "Enabled", "Auto"]);
+        assert_eq!(display_order(&names), names);
+    }
+
+    #[test]
+    fn shared_display_name_keeps_distinct_ids() {
+        let root = TestRoot::new("shared_display_name");
+        let controls: Vec<Control<Attribute>> = ["WakeOnLan", "WakeOnLanDock"]
+            .iter()
+            .map(|name| {
+                let path = root.attribute(
+                    name,
+                    &[
+                        ("type", "enumeration"),
+                        ("current_value", "Enabled"),
+                        ("possible_values", "Disabled;Enabled"),
+                        ("display_name", "Wake on LAN"),
+                    ],
+                );
+                let attribute = Attribute::try_from(path).unwrap();
+                Control::new(attribute, &Status::default(), &AccessMode::ReadOnly)
+            })
+            .collect();
+        assert_eq!(
+            controls[0].attribute().display_name(),
+            controls[1].attribute().display_name()
+        );
+        assert_ne!(controls[0].id_source(), controls[1].id_source());
+    }
+
+    #[test]
+    fn programmed_write_once_is_locked() {
+        let root = TestRoot::new("programmed_write_once");
+        let controls: Vec<Control<Attribute>> = [("AssetTag", "ABC"), ("OwnerName", "")]
+            .iter()
+            .map(|(name, current_value)| {
+                let path = root.attribute(
+                    name,
+                    &[
+                        ("type", "string"),
+                        ("current_value", current_value),
+                        ("default_value", ""),
+                        ("otp", "1"),
+                    ],
+                );
+                let attribute = Attribute::try_from(path).unwrap();
+                Control::new(attribute, &Status::default(), &AccessMode::ReadWrite)
+            })
+            .collect();
+        assert!(!controls[0].writable());
+        assert_eq!(
+            controls[0].lock_hint,
+            "One-time write Attribute is already programmed"
+        );
+        assert!(controls[1].writable());
+        assert!(controls[1]
+            .warnings()
+            .contains(&WRITE_ONCE_WARNING.to_string()));
+    }
+
+    #[test]
+    fn enumeration_widget_by_possible_values_count() {
+        let widgets: Vec<EnumerationWidget> = [0, 1, 3, 4, 20]
+            .into_iter()
+            .map(enumeration_widget)
+            .collect();
+        assert_eq!(
+            widgets,
+            [
+                EnumerationWidget::Label,
+                EnumerationWidget::RadioButtons,
+                EnumerationWidget::RadioButtons,
+                EnumerationWidget::ComboBox,
+                EnumerationWidget::ComboBox,
+            ]
+        );
+    }
+
+    #[test]
+    fn string_length_limits() {
+        assert!(!is_string_length_valid("ab", 4, 32));
+        assert!(!is_string_length_valid("  ab  ", 4, 32));
+        assert!(is_string_length_valid("abcd", 4, 32));
+        assert!(is_string_length_valid("äöüß", 4, 4));
+        assert!(!is_string_length_valid("abcde", 4, 4));
+    }
+
+    #[test]
+    fn reorder_does_not_wrap() {
+        let mut order = list(&["USB", "NVMe", "PXE"]);
+        move_up(&mut order, 0);
+        move_down(&mut order, 2);
+        assert_eq!(order, ["USB", "NVMe", "PXE"]);
+        move_up(&mut order, 2);
+        assert_eq!(order, ["USB", "PXE", "NVMe"]);
+        move_down(&mut order, 0);
+        assert_eq!(order, ["PXE", "USB", "NVMe"]);
+        move_down(&mut order, 3);
+        assert_eq!(order, ["PXE", "USB", "NVMe"]);
+    }
+}
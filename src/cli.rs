@@ -0,0 +1,538 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::profile;
+use crate::profile::Profile;
+use crate::sysfs_firmware_attributes::{
+    autodetect_root, raw_properties, read_attributes, Attribute, AttributeParser, AttributeValue,
+    Authentication, Mechanism, Role,
+};
+use clap::{Args, Subcommand, ValueEnum};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{fs, io};
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Print a schema describing the exported profile format
+    Schema {
+        #[arg(short, long, value_enum, default_value_t = SchemaFormat::JsonSchema)]
+        format: SchemaFormat,
+    },
+    /// List all attributes with their types and current values
+    List,
+    /// Print the current value of the attribute
+    Get {
+        /// Attribute name, for example "SecureBoot"
+        attribute: String,
+    },
+    /// Validate and write the value of the attribute
+    Set {
+        /// Attribute name, for example "SecureBoot"
+        attribute: String,
+        /// Value in the sysfs format, lists are delimited as in "current_value"
+        value: String,
+        #[command(flatten)]
+        authentication: AuthenticationArgs,
+    },
+    /// Write the value only if it differs, verify it and exit with non-zero code on mismatch
+    Ensure {
+        /// Attribute name, for example "SecureBoot"
+        attribute: String,
+        /// Value in the sysfs format, lists are delimited as in "current_value"
+        value: String,
+        #[command(flatten)]
+        authentication: AuthenticationArgs,
+    },
+    /// Export current values of all attributes as a JSON profile
+    Export {
+        /// Output file, stdout if not specified
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Include the enabled state of authentications, passwords are never exported
+        #[arg(long)]
+        authentications: bool,
+        /// Add display names, constraints and the machine identity in "_meta" sections for review
+        #[arg(long)]
+        annotated: bool,
+        /// Add the machine identity and fingerprint, import warns when it doesn't match the target
+        #[arg(long)]
+        fingerprint: bool,
+    },
+    /// Write values from a JSON profile created by the export, nothing is written if any value is invalid
+    Import {
+        /// Profile file
+        profile: PathBuf,
+        /// Only validate the profile and print what would be changed
+        #[arg(long)]
+        dry_run: bool,
+        #[command(flatten)]
+        authentication: AuthenticationArgs,
+    },
+    /// Generate a shell script with the writes the import of the profile would perform,
+    /// passwords are left as placeholders
+    Script {
+        /// Profile file
+        profile: PathBuf,
+        /// Output file, stdout if not specified
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Authenticate, import the profile and reboot if the firmware requires it.
+    /// Exits with 0 when no reboot was needed, 2 when the reboot was requested and 1 on failure
+    Apply {
+        /// Profile file
+        profile: PathBuf,
+        /// Reboot when the firmware reports pending changes
+        #[arg(long)]
+        reboot_if_needed: bool,
+        #[command(flatten)]
+        authentication: AuthenticationArgs,
+    },
+    /// Compare two exported profiles and print the attributes with different values.
+    /// Exits with 0 when the profiles are equal and 1 otherwise
+    Diff {
+        /// Profile file, for example of a reference machine
+        old: PathBuf,
+        /// Profile file to compare with
+        new: PathBuf,
+    },
+    /// Print exact contents of the attribute property files, passwords are redacted
+    Raw {
+        /// Attribute name, for example "SecureBoot"
+        attribute: String,
+        /// Copy the property files into a new subdirectory of this directory instead
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Authentication of the commands writing to the firmware
+#[derive(Args)]
+pub struct AuthenticationArgs {
+    /// Read the BIOS administrator password from the first line of stdin
+    #[arg(long)]
+    password_stdin: bool,
+    /// Authenticate with the password of this role instead, for example "power-on"
+    #[arg(long, requires = "password_stdin")]
+    role: Option<String>,
+}
+
+impl Command {
+    /// Whether the command may write to the firmware
+    pub fn writes(&self) -> bool {
+        match self {
+            Command::Set { .. } | Command::Ensure { .. } | Command::Apply { .. } => true,
+            Command::Import { dry_run, .. } => !dry_run,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SchemaFormat {
+    JsonSchema,
+}
+
+pub fn run(command: Command, root: Option<PathBuf>, knowledge_base: &KnowledgeBase) -> i32 {
+    let result = match command {
+        Command::Schema {
+            format: SchemaFormat::JsonSchema,
+        } => schema(),
+        Command::List => resolve_root(root).and_then(|root| list(&root)),
+        Command::Get { attribute } => resolve_root(root).and_then(|root| get(&root, &attribute)),
+        Command::Set {
+            attribute,
+            value,
+            authentication,
+        } => resolve_root(root).and_then(|root| {
+            authenticated(&root, &authentication, |root| {
+                set(root, &attribute, &value, knowledge_base)
+            })
+        }),
+        Command::Ensure {
+            attribute,
+            value,
+            authentication,
+        } => resolve_root(root).and_then(|root| {
+            authenticated(&root, &authentication, |root| {
+                ensure(root, &attribute, &value, knowledge_base)
+            })
+        }),
+        Command::Export {
+            output,
+            authentications,
+            annotated,
+            fingerprint,
+        } => resolve_root(root)
+            .and_then(|root| export(&root, output, authentications, annotated, fingerprint)),
+        Command::Import {
+            profile,
+            dry_run,
+            authentication,
+        } => resolve_root(root).and_then(|root| {
+            authenticated(&root, &authentication, |root| {
+                import(root, &profile, dry_run, knowledge_base)
+            })
+        }),
+        Command::Script { profile, output } => {
+            resolve_root(root).and_then(|root| script(&root, &profile, output))
+        }
+        Command::Apply {
+            profile,
+            reboot_if_needed,
+            authentication,
+        } => resolve_root(root).and_then(|root| {
+            authenticated(&root, &authentication, |root| {
+                apply(root, &profile, reboot_if_needed, knowledge_base)
+            })
+        }),
+        Command::Diff { old, new } => diff(&old, &new),
+        Command::Raw { attribute, output } => {
+            resolve_root(root).and_then(|root| raw(&root, &attribute, output))
+        }
+    };
+    match result {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("{}", err);
+            1
+        }
+    }
+}
+
+fn resolve_root(root: Option<PathBuf>) -> Result<PathBuf, Box<dyn Error>> {
+    if let Some(root) = root {
+        return Ok(root);
+    }
+    let mut roots = autodetect_root();
+    match roots.len() {
+        0 => Err("Firmware Attributes root not found".into()),
+        1 => Ok(roots.remove(0)),
+        _ => Err(format!(
+            "Several Firmware Attributes roots found {:?}, select one with --path",
+            roots
+        )
+        .into()),
+    }
+}
+
+fn schema() -> Result<i32, Box<dyn Error>> {
+    println!("{}", serde_json::to_string_pretty(&profile::json_schema())?);
+    Ok(0)
+}
+
+fn list(root: &Path) -> Result<i32, Box<dyn Error>> {
+    let mut attributes = read_attributes(root)?;
+    attributes.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let mut failed = false;
+    for (name, attribute) in attributes {
+        match attribute.and_then(|attribute| {
+            let value = attribute.current_value()?;
+            Ok((attribute.type_description(), attribute.format_value(&value)))
+        }) {
+            Ok((attribute_type, value)) => println!("{}\t{}\t{}", name, attribute_type, value),
+            Err(err) => {
+                eprintln!("{}: {}", name, err);
+                failed = true;
+            }
+        }
+    }
+    Ok(if failed { 1 } else { 0 })
+}
+
+fn get(root: &Path, name: &str) -> Result<i32, Box<dyn Error>> {
+    let attribute = Attribute::attribute(root, name)?;
+    println!("{}", attribute.format_value(&attribute.current_value()?));
+    Ok(0)
+}
+
+fn set(
+    root: &Path,
+    name: &str,
+    value: &str,
+    knowledge_base: &KnowledgeBase,
+) -> Result<i32, Box<dyn Error>> {
+    let attribute = Attribute::attribute(root, name)?;
+    let value = attribute.parse_value(value)?;
+    let companions_written = write_checked(root, name, &attribute, &value, knowledge_base)?;
+    println!("{}: {:?}", name, attribute.format_value(&value));
+    Ok(if companions_written { 0 } else { 1 })
+}
+
+/// Validates and writes the value followed by the companion writes from the knowledge base,
+/// `false` when any of the companion writes failed
+fn write_checked(
+    root: &Path,
+    name: &str,
+    attribute: &Attribute,
+    value: &AttributeValue,
+    knowledge_base: &KnowledgeBase,
+) -> Result<bool, Box<dyn Error>> {
+    // Refuses read-only Attributes and values outside of the constraints
+    attribute.validate_value(value)?;
+    attribute.write_value(value)?;
+    let mut companions_written = true;
+    for (companion, result) in knowledge_base.write_companions(root, name) {
+        match result {
+            Ok(()) => println!("{}: written after {}", companion, name),
+            Err(err) => {
+                eprintln!("{}: {}", companion, err);
+                companions_written = false;
+            }
+        }
+    }
+    Ok(companions_written)
+}
+
+fn ensure(
+    root: &Path,
+    name: &str,
+    value: &str,
+    knowledge_base: &KnowledgeBase,
+) -> Result<i32, Box<dyn Error>> {
+    let attribute = Attribute::attribute(root, name)?;
+    let expected = attribute.parse_value(value)?;
+    let before = attribute.current_value()?;
+    let changed = before != expected;
+    let companions_written =
+        !changed || write_checked(root, name, &attribute, &expected, knowledge_base)?;
+    let after = attribute.current_value()?;
+    if after != expected {
+        eprintln!(
+            "{}: value {:?} was not applied, current value is {:?}",
+            name,
+            attribute.format_value(&expected),
+            attribute.format_value(&after)
+        );
+        return Ok(1);
+    }
+    if changed {
+        println!(
+            "{}: changed {:?} -> {:?}",
+            name,
+            attribute.format_value(&before),
+            attribute.format_value(&after)
+        );
+    } else {
+        println!("{}: unchanged {:?}", name, attribute.format_value(&after));
+    }
+    Ok(if companions_written { 0 } else { 1 })
+}
+
+fn export(
+    root: &Path,
+    output: Option<PathBuf>,
+    with_authentications: bool,
+    annotated: bool,
+    fingerprint: bool,
+) -> Result<i32, Box<dyn Error>> {
+    let mut profile = Profile::read(root, with_authentications)?;
+    if annotated {
+        profile.annotate(root)?;
+    } else if fingerprint {
+        profile.describe_machine(root)?;
+    }
+    match output {
+        Some(output) => profile.write(&output)?,
+        None => println!("{}", serde_json::to_string_pretty(&profile)?),
+    }
+    Ok(0)
+}
+
+fn import(
+    root: &Path,
+    profile: &Path,
+    dry_run: bool,
+    knowledge_base: &KnowledgeBase,
+) -> Result<i32, Box<dyn Error>> {
+    let profile = Profile::from_file(profile)?;
+    let warnings = profile.warnings(root);
+    let validation = profile.validate_staged(root, &warnings);
+    if dry_run || !validation.failed.is_empty() {
+        println!("{}", validation);
+        return Ok(if validation.failed.is_empty() { 0 } else { 1 });
+    }
+    let report = profile.apply(root, &warnings, knowledge_base);
+    println!("{}", report);
+    Ok(if report.failed.is_empty() { 0 } else { 1 })
+}
+
+fn script(root: &Path, profile: &Path, output: Option<PathBuf>) -> Result<i32, Box<dyn Error>> {
+    let profile = Profile::from_file(profile)?;
+    let script = profile.script(root)?;
+    match output {
+        Some(output) => fs::write(output, script)?,
+        None => print!("{}", script),
+    }
+    Ok(0)
+}
+
+fn diff(old: &Path, new: &Path) -> Result<i32, Box<dyn Error>> {
+    let old_profile = Profile::from_file(old)?;
+    let new_profile = Profile::from_file(new)?;
+    let diff = old_profile.diff(&new_profile);
+    for (name, change) in &diff.changed {
+        println!("{}: {}", name, change);
+    }
+    for (path, names) in [(old, &diff.only_in_old), (new, &diff.only_in_new)] {
+        if !names.is_empty() {
+            println!("Only in {}: {}", path.display(), names.join(", "));
+        }
+    }
+    Ok(if diff.is_empty() { 0 } else { 1 })
+}
+
+fn apply(
+    root: &Path,
+    profile: &Path,
+    reboot_if_needed: bool,
+    knowledge_base: &KnowledgeBase,
+) -> Result<i32, Box<dyn Error>> {
+    let profile = Profile::from_file(profile)?;
+    let warnings = profile.warnings(root);
+    let validation = profile.validate_staged(root, &warnings);
+    let report = if validation.failed.is_empty() {
+        profile.apply(root, &warnings, knowledge_base)
+    } else {
+        validation
+    };
+    println!("{}", report);
+    if !report.failed.is_empty() {
+        return Ok(1);
+    }
+    if reboot_if_needed && Attribute::pending_reboot(root)? {
+        println!("Rebooting...");
+        system_shutdown::reboot()?;
+        return Ok(2);
+    }
+    Ok(0)
+}
+
+/// Runs the command between the login and the logout requested by the arguments
+fn authenticated(
+    root: &Path,
+    args: &AuthenticationArgs,
+    command: impl FnOnce(&Path) -> Result<i32, Box<dyn Error>>,
+) -> Result<i32, Box<dyn Error>> {
+    let authentication = if args.password_stdin {
+        let mut password = String::new();
+        io::stdin().read_line(&mut password)?;
+        let password = password.trim_end_matches(['\r', '\n']);
+        let authentication = select_authentication(root, args.role.as_deref())?;
+        authentication.authenticate_with_password(password)?;
+        Some(authentication)
+    } else {
+        None
+    };
+    let result = command(root);
+    if let Some(authentication) = authentication {
+        // Logout
+        let _ = authentication.authenticate_with_password("");
+    }
+    result
+}
+
+fn select_authentication(
+    root: &Path,
+    role: Option<&str>,
+) -> Result<Authentication, Box<dyn Error>> {
+    match role {
+        Some(role) => role_authentication(root, role),
+        None => Ok(admin_authentication(root)?
+            .ok_or("No enabled password authentication found, --password-stdin is not needed")?),
+    }
+}
+
+/// The BIOS administrator is preferred, any other enabled password authentication is used
+/// when the driver doesn't report it
+fn admin_authentication(root: &Path) -> Result<Option<Authentication>, Box<dyn Error>> {
+    let mut fallback = None;
+    for name in Attribute::authentications_names(root)? {
+        let authentication = Attribute::authentication(root, &name)?;
+        if !authentication.is_enabled || !matches!(authentication.mechanism, Mechanism::Password) {
+            continue;
+        }
+        if matches!(authentication.role, Role::BiosAdmin) {
+            return Ok(Some(authentication));
+        }
+        fallback.get_or_insert(authentication);
+    }
+    Ok(fallback)
+}
+
+fn role_authentication(root: &Path, role: &str) -> Result<Authentication, Box<dyn Error>> {
+    let role = Role::from_str(role).map_err(|_| format!("Unknown role {:?}", role))?;
+    for name in Attribute::authentications_names(root)? {
+        let authentication = Attribute::authentication(root, &name)?;
+        if authentication.role.as_ref() != role.as_ref() {
+            continue;
+        }
+        if !authentication.is_enabled {
+            return Err(format!(
+                "Authentication {:?} of role {} is not enabled",
+                name,
+                role.as_ref()
+            )
+            .into());
+        }
+        if !matches!(authentication.mechanism, Mechanism::Password) {
+            return Err(format!(
+                "Authentication {:?} of role {} uses unsupported mechanism {}",
+                name,
+                role.as_ref(),
+                authentication.mechanism.as_ref()
+            )
+            .into());
+        }
+        return Ok(authentication);
+    }
+    Err(format!("No authentication with role {} found", role.as_ref()).into())
+}
+
+fn raw(root: &Path, name: &str, output: Option<PathBuf>) -> Result<i32, Box<dyn Error>> {
+    let attribute = Attribute::attribute(root, name)?;
+    match output {
+        Some(output) => println!("{}", profile::export_raw(&attribute, &output)?.display()),
+        None => {
+            for (property, content) in raw_properties(attribute.path())? {
+                println!("{}: {:?}", property, String::from_utf8_lossy(&content));
+            }
+        }
+    }
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_root::TestRoot;
+
+    #[test]
+    fn authentication_selected_by_role() {
+        let test_root = TestRoot::new("authentication_selected_by_role");
+        test_root.authentication("Power-on", "power-on", true);
+        test_root.authentication("Admin", "bios-admin", true);
+        test_root.authentication("System", "system", false);
+        let name = |role| {
+            select_authentication(&test_root.root, role)
+                .map(|authentication| authentication.login)
+                .map_err(|err| err.to_string())
+        };
+        let selected = [name(None), name(Some("bios-admin")), name(Some("power-on"))];
+        let disabled = name(Some("system"));
+        let unknown = name(Some("owner"));
+
+        assert_eq!(
+            selected,
+            [
+                Ok("Admin".to_string()),
+                Ok("Admin".to_string()),
+                Ok("Power-on".to_string())
+            ]
+        );
+        assert!(disabled.is_err_and(|err| err.contains("not enabled")));
+        assert!(unknown.is_err_and(|err| err.contains("Unknown role")));
+    }
+}
@@ -0,0 +1,239 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::sysfs_firmware_attributes::{Attribute, AttributeError, AttributeParser};
+use log::info;
+use serde::Deserialize;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+const BUNDLED_KNOWLEDGE_BASE: &str = include_str!("knowledge_base.json");
+/// Comma separated name patterns of additional Attributes that require a confirmation
+#[cfg(feature = "gui")]
+pub const ENV_SENSITIVE_ATTRIBUTES: &str = "FW_ATTR_EDITOR_SENSITIVE";
+#[cfg(feature = "gui")]
+const SENSITIVE_WARNING: &str =
+    "Marked as sensitive, the change can affect the boot or the security.";
+
+/// Known consequences of changing well-known Attributes
+#[derive(Debug, Clone, Default)]
+pub struct KnowledgeBase {
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Entry {
+    /// Attribute name pattern, `*` matches any sequence of characters
+    pattern: String,
+    /// Shown before the change in the GUI
+    #[cfg(feature = "gui")]
+    #[serde(default)]
+    warning: Option<String>,
+    /// Follow-up writes required for the change to take effect
+    #[serde(default)]
+    companions: Vec<Companion>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Companion {
+    attribute: String,
+    /// Value in the sysfs format
+    value: String,
+}
+
+impl KnowledgeBase {
+    pub fn bundled() -> Self {
+        Self {
+            entries: serde_json::from_str(BUNDLED_KNOWLEDGE_BASE)
+                .expect("Bundled knowledge base is valid"),
+        }
+    }
+
+    /// Adds entries from a user file in the same format as the bundled one
+    pub fn extend_from_file(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let entries: Vec<Entry> = serde_json::from_str(&fs::read_to_string(path)?)?;
+        self.entries.extend(entries);
+        Ok(())
+    }
+
+    /// Changes of the matching Attributes require a confirmation, like the ones with warnings
+    #[cfg(feature = "gui")]
+    pub fn add_sensitive(&mut self, pattern: &str) {
+        self.entries.push(Entry {
+            pattern: pattern.to_string(),
+            warning: Some(SENSITIVE_WARNING.to_string()),
+            companions: Vec::new(),
+        });
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn add_sensitive_from_env(&mut self) {
+        if let Ok(patterns) = std::env::var(ENV_SENSITIVE_ATTRIBUTES) {
+            for pattern in patterns.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                self.add_sensitive(pattern);
+            }
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn warnings(&self, attribute_name: &str) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter(|entry| matches_pattern(&entry.pattern, attribute_name))
+            .filter_map(|entry| entry.warning.clone())
+            .collect()
+    }
+
+    /// Performs the follow-up writes declared for the Attribute after it was written,
+    /// returns the companion names with the write results
+    pub fn write_companions(
+        &self,
+        root: &Path,
+        attribute_name: &str,
+    ) -> Vec<(String, Result<(), AttributeError>)> {
+        self.entries
+            .iter()
+            .filter(|entry| matches_pattern(&entry.pattern, attribute_name))
+            .flat_map(|entry| entry.companions.iter())
+            .map(|companion| {
+                info!(
+                    "Companion write {:?}={:?} after {:?}",
+                    companion.attribute, companion.value, attribute_name
+                );
+                // User files can name any Attribute, the firmware constraints apply as for edits
+                let result = Attribute::attribute(root, &companion.attribute).and_then(|attr| {
+                    let value = attr.parse_value(&companion.value)?;
+                    attr.validate_value(&value)?;
+                    attr.write_value(&value)
+                });
+                (companion.attribute.clone(), result)
+            })
+            .collect()
+    }
+}
+
+/// Case-insensitive match, `*` matches any sequence of characters
+pub fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let name = name.to_lowercase();
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::Profile;
+    use crate::sysfs_firmware_attributes::AttributeValue;
+    use crate::test_root::{current_value, TestRoot};
+
+    /// Entries in the format of the knowledge base files
+    fn knowledge_base(json: &str) -> KnowledgeBase {
+        KnowledgeBase {
+            entries: serde_json::from_str(json).unwrap(),
+        }
+    }
+
+    #[test]
+    fn pattern_matching() {
+        assert!(matches_pattern("*SecureBoot*", "SecureBoot"));
+        assert!(matches_pattern("*secureboot*", "UefiSecureBootMode"));
+        assert!(matches_pattern("*Sata*Mode*", "SataControllerMode"));
+        assert!(matches_pattern("Boot*", "BootOrder"));
+        assert!(matches_pattern("BootOrder", "bootorder"));
+        assert!(!matches_pattern("BootOrder", "BootOrderLock"));
+        assert!(!matches_pattern("Boot*", "FastBoot"));
+        assert!(!matches_pattern("*Sata*Mode*", "ModeSata"));
+    }
+
+    #[test]
+    fn companion_written_after_primary() {
+        let test_root = TestRoot::new("companion_written_after_primary");
+        let root = &test_root.root;
+        for (name, current_value, possible_values) in [
+            ("SecureBoot", "Disabled", "Enabled;Disabled"),
+            ("SaveChanges", "No", "Yes;No"),
+        ] {
+            test_root.attribute(
+                name,
+                &[
+                    ("type", "enumeration"),
+                    ("current_value", current_value),
+                    ("possible_values", possible_values),
+                ],
+            );
+        }
+        let knowledge_base = knowledge_base(
+            r#"[{"pattern": "*SecureBoot*", "companions": [{"attribute": "SaveChanges", "value": "Yes"}]}]"#,
+        );
+        let read = |name: &str| {
+            current_value(&root.join("attributes").join(name))
+                .trim_end()
+                .to_string()
+        };
+        let profile = |value: AttributeValue| {
+            let mut profile = Profile::read(root, false).unwrap();
+            profile.attributes.retain(|name, _| name == "SecureBoot");
+            profile
+                .attributes
+                .get_mut("SecureBoot")
+                .unwrap()
+                .current_value = value;
+            profile
+        };
+
+        // A value of another type fails the primary write
+        let report = profile(AttributeValue::Integer(1)).apply(root, &[], &knowledge_base);
+        let saved_after_failure = read("SaveChanges");
+        let failed: Vec<&String> = report.failed.iter().map(|(name, _)| name).collect();
+
+        let report = profile("Enabled".to_string().into()).apply(root, &[], &knowledge_base);
+        let values = (read("SecureBoot"), read("SaveChanges"));
+
+        assert_eq!(failed, ["SecureBoot"]);
+        assert_eq!(saved_after_failure, "No");
+        assert_eq!(
+            report.changed,
+            ["SecureBoot", "SaveChanges (after SecureBoot)"]
+        );
+        assert_eq!(values, ("Enabled".to_string(), "Yes".to_string()));
+    }
+
+    #[test]
+    fn invalid_companion_refused() {
+        let test_root = TestRoot::new("invalid_companion_refused");
+        let root = &test_root.root;
+        let save_changes = test_root.attribute(
+            "SaveChanges",
+            &[
+                ("type", "enumeration"),
+                ("current_value", "No"),
+                ("possible_values", "Yes;No"),
+            ],
+        );
+        let knowledge_base = knowledge_base(
+            r#"[{"pattern": "SecureBoot", "companions": [{"attribute": "SaveChanges", "value": "Always"}]}]"#,
+        );
+
+        let results = knowledge_base.write_companions(root, "SecureBoot");
+        assert!(matches!(
+            results.as_slice(),
+            [(name, Err(AttributeError::InvalidValue(_)))] if name == "SaveChanges"
+        ));
+        assert_eq!(current_value(&save_changes), "No\n");
+    }
+}
@@ -0,0 +1,11 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Reading and writing Linux Firmware Attributes exposed in sysfs,
+//! see <https://docs.kernel.org/admin-guide/abi-testing.html#abi-sys-class-firmware-attributes>
+
+pub mod sysfs_firmware_attributes;
+
+pub use sysfs_firmware_attributes::{
+    firmware_attributes_pending_reboot, Attribute, AttributeError, AttributeParser, Authentication,
+    CommonAttribute, Mechanism, ReadableAttribute, Role, WriteableAttribute,
+};
@@ -1,12 +1,25 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use crate::application::{Application, Status};
+#[cfg(feature = "gui")]
+use crate::application::{Application, GuiArgs, Settings, Status, Theme};
+use crate::cli::Command;
+use crate::knowledge_base::KnowledgeBase;
 use clap::Parser;
+use fw_attr_editor::sysfs_firmware_attributes;
+use log::error;
+use std::error::Error;
+#[cfg(feature = "gui")]
 use std::path::Path;
+use std::path::PathBuf;
 
-mod sysfs_firmware_attributes;
-
+#[cfg(feature = "gui")]
 mod application;
+mod cli;
+mod knowledge_base;
+mod profile;
+#[cfg(test)]
+#[path = "sysfs_firmware_attributes/test_root.rs"]
+mod test_root;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -19,29 +32,88 @@ struct Args {
     /// Can be specified with LOG_STYLE env variable. Default: warn;
     #[arg(short, long)]
     log_level: Option<String>,
+
+    /// JSON file with additional warnings for well-known attributes,
+    /// in the same format as the bundled knowledge base
+    #[arg(long)]
+    knowledge_base: Option<PathBuf>,
+
+    /// Open the firmware read-only without the login screen, subcommands that write are refused
+    #[arg(long)]
+    read_only: bool,
+
+    #[cfg(feature = "gui")]
+    #[command(flatten)]
+    gui: GuiArgs,
+
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
-fn main() -> Result<(), eframe::Error> {
-    let args = Args::parse();
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = Args::parse();
     let env = env_logger::Env::default()
-        .filter_or("LOG_LEVEL", args.log_level.unwrap_or("warn".to_string()))
+        .filter_or("LOG_LEVEL", args.log_level.as_deref().unwrap_or("warn"))
         .write_style_or("LOG_STYLE", "always");
 
     env_logger::init_from_env(env);
 
+    let mut knowledge_base = KnowledgeBase::bundled();
+    if let Some(path) = &args.knowledge_base {
+        if let Err(err) = knowledge_base.extend_from_file(path) {
+            error!("Cannot load knowledge base {:?}: {}", path, err);
+        }
+    }
+
+    if let Some(command) = args.command.take() {
+        if args.read_only && command.writes() {
+            error!("The command writes to the firmware, it is refused with --read-only");
+            std::process::exit(1);
+        }
+        std::process::exit(cli::run(
+            command,
+            args.path.map(PathBuf::from),
+            &knowledge_base,
+        ));
+    }
+    run_gui(args, knowledge_base)
+}
+
+#[cfg(feature = "gui")]
+fn run_gui(args: Args, knowledge_base: KnowledgeBase) -> Result<(), Box<dyn Error>> {
     let options = eframe::NativeOptions {
-        initial_window_size: Some(egui::vec2(640.0, 480.0)),
+        // Used on the first launch, the stored geometry takes precedence afterwards
+        initial_window_size: Some(egui::vec2(640.0, 720.0)),
+        persist_window: !args.gui.no_persist_window,
+        follow_system_theme: args.gui.theme.is_none_or(|theme| theme == Theme::Auto),
         ..Default::default()
     };
-    let application = if let Some(root) = args.path {
-        Application::bios_admin_authentication(Path::new(&root), &Status::default())
-            .unwrap_or(Application::select_root(Vec::new()))
+    let status = Status::new(Settings::from_args(
+        args.gui,
+        knowledge_base,
+        args.read_only,
+    ));
+    let mut application = if let Some(root) = args.path {
+        Application::bios_admin_authentication(Path::new(&root), &status)
+            .unwrap_or(Application::select_root(Vec::new(), &status))
     } else {
-        Application::autodetect_root()
+        Application::autodetect_root(&status)
     };
     eframe::run_native(
         "BIOS Settings Editor",
         options,
-        Box::new(|_cc| Box::new(application)),
-    )
+        Box::new(|cc| {
+            if let Some(storage) = cc.storage {
+                application.restore(storage);
+            }
+            application.apply_theme(&cc.egui_ctx);
+            Box::new(application)
+        }),
+    )?;
+    Ok(())
+}
+
+#[cfg(not(feature = "gui"))]
+fn run_gui(_args: Args, _knowledge_base: KnowledgeBase) -> Result<(), Box<dyn Error>> {
+    Err("Built without the GUI, run one of the subcommands, see --help".into())
 }
@@ -0,0 +1,825 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::sysfs_firmware_attributes::{
+    firmware_attributes_pending_reboot, raw_properties, read_attributes, Attribute, AttributeError,
+    AttributeParser, AttributeValue, Authentication, Role, TYPE_ENUMERATION, TYPE_ENUMERATION_LIST,
+    TYPE_INTEGER, TYPE_MAP, TYPE_ORDERED_LIST, TYPE_RAW, TYPE_STRING,
+};
+use chrono::Local;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+const PATH_HOSTNAME: &str = "/proc/sys/kernel/hostname";
+const PATH_DMI: &str = "/sys/class/dmi/id";
+/// Identify the model, BIOS version is left out as it changes with updates
+const DMI_IDENTIFIERS: &[&str] = &[
+    "sys_vendor",
+    "product_name",
+    "product_version",
+    "board_vendor",
+    "board_name",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
+    /// Review annotations, ignored on import
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ProfileMeta>,
+    /// Changes waiting for a reboot at the time of the export, ignored on import
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_reboot: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authentications: Option<BTreeMap<String, AuthenticationState>>,
+    pub attributes: BTreeMap<String, ProfileEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileEntry {
+    #[serde(rename = "type")]
+    pub attribute_type: String,
+    pub current_value: AttributeValue,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<AttributeValue>,
+    /// Review annotations, ignored on import
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<EntryMeta>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileMeta {
+    pub hostname: Option<String>,
+    pub exported_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<Fingerprint>,
+}
+
+/// Machine model identity used to warn about profiles from other models
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    pub dmi: BTreeMap<String, String>,
+    /// Hash of the Attribute names and types
+    pub attributes: String,
+}
+
+impl Fingerprint {
+    pub fn read(root: &Path) -> Result<Self, AttributeError> {
+        let dmi = DMI_IDENTIFIERS
+            .iter()
+            .filter_map(|name| {
+                let value = fs::read_to_string(Path::new(PATH_DMI).join(name)).ok()?;
+                Some((name.to_string(), value.trim().to_string()))
+            })
+            .collect();
+        let mut attributes: Vec<String> = read_attributes(root)?
+            .into_iter()
+            .map(|(name, attribute)| match attribute {
+                Ok(attribute) => format!("{}:{}", name, attribute.raw_type()),
+                Err(_) => name,
+            })
+            .collect();
+        attributes.sort();
+        Ok(Self {
+            dmi,
+            attributes: format!("{:016x}", fnv1a(attributes.join("\n").as_bytes())),
+        })
+    }
+
+    pub fn differences(&self, other: &Self) -> Vec<String> {
+        let mut differences: Vec<String> = self
+            .dmi
+            .iter()
+            .filter(|(name, value)| other.dmi.get(*name).is_some_and(|other| other != *value))
+            .map(|(name, value)| format!("{} {:?} differs from {:?}", name, value, other.dmi[name]))
+            .collect();
+        if self.attributes != other.attributes {
+            differences.push("the set of Attributes differs".to_string());
+        }
+        differences
+    }
+}
+
+/// Stable across builds unlike the std hasher
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryMeta {
+    pub display_name: String,
+    pub raw_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed: Option<String>,
+}
+
+/// Authentication configuration without any secret material
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthenticationState {
+    pub role: String,
+    pub mechanism: String,
+    pub is_enabled: bool,
+}
+
+/// Difference between two values of the same Attribute.
+/// Ordered lists are compared as sequences and enumeration lists as sets
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueChange {
+    /// The current value is None if it cannot be read
+    Changed {
+        from: Option<AttributeValue>,
+        to: AttributeValue,
+    },
+    /// Same elements in another order
+    Reordered { from: Vec<String>, to: Vec<String> },
+    Membership {
+        added: Vec<String>,
+        removed: Vec<String>,
+    },
+}
+
+impl ValueChange {
+    /// Returns None if the values are equal according to the semantics of the Attribute type
+    pub fn diff(attribute_type: &str, from: &AttributeValue, to: &AttributeValue) -> Option<Self> {
+        match (from, to) {
+            (AttributeValue::List(from), AttributeValue::List(to)) => {
+                let added: Vec<String> = to.iter().filter(|v| !from.contains(v)).cloned().collect();
+                let removed: Vec<String> =
+                    from.iter().filter(|v| !to.contains(v)).cloned().collect();
+                if !added.is_empty() || !removed.is_empty() {
+                    Some(Self::Membership { added, removed })
+                } else if attribute_type == TYPE_ORDERED_LIST && from != to {
+                    Some(Self::Reordered {
+                        from: from.clone(),
+                        to: to.clone(),
+                    })
+                } else {
+                    None
+                }
+            }
+            _ if from == to => None,
+            _ => Some(Self::Changed {
+                from: Some(from.clone()),
+                to: to.clone(),
+            }),
+        }
+    }
+}
+
+impl Display for ValueChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValueChange::Changed {
+                from: Some(from),
+                to,
+            } => write!(f, "{:?} -> {:?}", from, to),
+            ValueChange::Changed { from: None, to } => write!(f, "unknown -> {:?}", to),
+            ValueChange::Reordered { from, to } => {
+                write!(f, "reordered [{}] -> [{}]", from.join(", "), to.join(", "))
+            }
+            ValueChange::Membership { added, removed } => {
+                let mut parts = Vec::new();
+                if !added.is_empty() {
+                    parts.push(format!("added [{}]", added.join(", ")));
+                }
+                if !removed.is_empty() {
+                    parts.push(format!("removed [{}]", removed.join(", ")));
+                }
+                write!(f, "{}", parts.join(", "))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    /// Valid values that differ from the current ones, filled by validation only
+    pub pending: Vec<(String, ValueChange)>,
+    pub changed: Vec<String>,
+    pub unchanged: Vec<String>,
+    pub skipped: Vec<String>,
+    pub failed: Vec<(String, AttributeError)>,
+    pub warnings: Vec<String>,
+}
+
+/// Differences between two profiles, for example of a reference and a field machine
+#[derive(Debug, Default)]
+pub struct ProfileDiff {
+    pub changed: Vec<(String, ValueChange)>,
+    pub only_in_old: Vec<String>,
+    pub only_in_new: Vec<String>,
+}
+
+impl ProfileDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.only_in_old.is_empty() && self.only_in_new.is_empty()
+    }
+}
+
+impl Profile {
+    /// Values are compared with the semantics of the type in the new profile
+    pub fn diff(&self, new: &Profile) -> ProfileDiff {
+        let mut diff = ProfileDiff::default();
+        for (name, old_entry) in &self.attributes {
+            match new.attributes.get(name) {
+                Some(new_entry) => {
+                    if let Some(change) = ValueChange::diff(
+                        &new_entry.attribute_type,
+                        &old_entry.current_value,
+                        &new_entry.current_value,
+                    ) {
+                        diff.changed.push((name.clone(), change));
+                    }
+                }
+                None => diff.only_in_old.push(name.clone()),
+            }
+        }
+        diff.only_in_new = new
+            .attributes
+            .keys()
+            .filter(|name| !self.attributes.contains_key(*name))
+            .cloned()
+            .collect();
+        diff
+    }
+
+    pub fn read(root: &Path, with_authentications: bool) -> Result<Self, AttributeError> {
+        let mut attributes = BTreeMap::new();
+        for (name, attribute) in read_attributes(root)? {
+            let attribute = match attribute {
+                Ok(attribute) => attribute,
+                Err(err) => {
+                    warn!("Attribute {:?} skipped: {}", name, err);
+                    continue;
+                }
+            };
+            match attribute.current_value() {
+                Ok(current_value) => {
+                    attributes.insert(
+                        name,
+                        ProfileEntry {
+                            attribute_type: attribute.attribute_type().to_string(),
+                            current_value,
+                            default_value: attribute.default_value(),
+                            meta: None,
+                        },
+                    );
+                }
+                Err(err) => warn!("Attribute {:?} skipped: {}", name, err),
+            }
+        }
+        let authentications = if with_authentications {
+            Some(authentication_states(root)?)
+        } else {
+            None
+        };
+        let pending_reboot = match firmware_attributes_pending_reboot(root) {
+            Ok(pending_reboot) => Some(pending_reboot),
+            Err(err) => {
+                warn!("Pending reboot state skipped: {}", err);
+                None
+            }
+        };
+        Ok(Self {
+            meta: None,
+            pending_reboot,
+            authentications,
+            attributes,
+        })
+    }
+
+    /// Adds the machine identity and fingerprint
+    pub fn describe_machine(&mut self, root: &Path) -> Result<(), AttributeError> {
+        self.meta = Some(ProfileMeta {
+            hostname: fs::read_to_string(PATH_HOSTNAME)
+                .ok()
+                .map(|s| s.trim().to_string()),
+            exported_at: Local::now().to_rfc3339(),
+            fingerprint: Some(Fingerprint::read(root)?),
+        });
+        Ok(())
+    }
+
+    /// Adds the machine identity and a description of every Attribute for human review
+    pub fn annotate(&mut self, root: &Path) -> Result<(), AttributeError> {
+        self.describe_machine(root)?;
+        for (name, entry) in self.attributes.iter_mut() {
+            if let Ok(attribute) = Attribute::attribute(root, name) {
+                entry.meta = Some(EntryMeta {
+                    display_name: attribute.display_name().clone(),
+                    raw_type: attribute.raw_type().clone(),
+                    allowed: allowed_values(&attribute),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Exports all Attributes of the root into a new timestamped file inside the directory
+    #[cfg(feature = "gui")]
+    pub fn write_snapshot(root: &Path, directory: &Path) -> Result<PathBuf, AttributeError> {
+        let path = directory.join(format!(
+            "snapshot-{}.json",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        export_json(root, &path)?;
+        Ok(path)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, AttributeError> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?).map_err(io::Error::from)?)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), AttributeError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(
+            path,
+            serde_json::to_string_pretty(self).map_err(io::Error::from)?,
+        )?;
+        Ok(())
+    }
+
+    /// Validates every value of the profile against the root without writing anything,
+    /// the warnings of [Profile::warnings] are passed to the report
+    pub fn validate_staged(&self, root: &Path, warnings: &[String]) -> BatchReport {
+        let mut report = BatchReport {
+            warnings: warnings.to_vec(),
+            ..Default::default()
+        };
+        for (name, entry) in &self.attributes {
+            let attribute = match Attribute::attribute(root, name) {
+                Ok(attribute) => attribute,
+                Err(_) => {
+                    report.skipped.push(name.clone());
+                    continue;
+                }
+            };
+            let change = match attribute.current_value() {
+                Ok(current_value) => match ValueChange::diff(
+                    &entry.attribute_type,
+                    &current_value,
+                    &entry.current_value,
+                ) {
+                    Some(change) => change,
+                    None => {
+                        report.unchanged.push(name.clone());
+                        continue;
+                    }
+                },
+                Err(_) => ValueChange::Changed {
+                    from: None,
+                    to: entry.current_value.clone(),
+                },
+            };
+            match attribute.validate_value(&entry.current_value) {
+                Ok(()) => report.pending.push((name.clone(), change)),
+                Err(err) => report.failed.push((name.clone(), err)),
+            }
+        }
+        report
+    }
+
+    pub fn apply(
+        &self,
+        root: &Path,
+        warnings: &[String],
+        knowledge_base: &KnowledgeBase,
+    ) -> BatchReport {
+        let mut report = BatchReport {
+            warnings: warnings.to_vec(),
+            ..Default::default()
+        };
+        for (name, entry) in &self.attributes {
+            let attribute = match Attribute::attribute(root, name) {
+                Ok(attribute) => attribute,
+                Err(err) => {
+                    warn!("Attribute {:?} skipped: {}", name, err);
+                    report.skipped.push(name.clone());
+                    continue;
+                }
+            };
+            match attribute.current_value() {
+                Ok(current_value)
+                    if ValueChange::diff(
+                        &entry.attribute_type,
+                        &current_value,
+                        &entry.current_value,
+                    )
+                    .is_none() =>
+                {
+                    report.unchanged.push(name.clone())
+                }
+                _ => match attribute.write_value(&entry.current_value) {
+                    Ok(()) => {
+                        report.changed.push(name.clone());
+                        for (companion, result) in knowledge_base.write_companions(root, name) {
+                            let companion = format!("{} (after {})", companion, name);
+                            match result {
+                                Ok(()) => report.changed.push(companion),
+                                Err(err) => report.failed.push((companion, err)),
+                            }
+                        }
+                    }
+                    Err(err) => report.failed.push((name.clone(), err)),
+                },
+            }
+        }
+        report
+    }
+
+    /// Shell script with the writes the import would perform on the root, for review and manual
+    /// execution. Passwords are never included, authentication is left as a placeholder
+    pub fn script(&self, root: &Path) -> Result<String, AttributeError> {
+        let mut lines = vec![
+            "#!/bin/sh".to_string(),
+            format!(
+                "# Generated by fw-attr-editor at {} for {}",
+                Local::now().to_rfc3339(),
+                root.display()
+            ),
+            "set -e".to_string(),
+        ];
+        lines.extend(
+            self.fingerprint_warnings(root)
+                .into_iter()
+                .map(|warning| format!("# Warning: {}", warning)),
+        );
+        let authentications: Vec<Authentication> = Attribute::authentications_names(root)?
+            .iter()
+            .filter_map(|name| Attribute::authentication(root, name).ok())
+            .filter(|auth| auth.is_enabled && matches!(auth.role, Role::BiosAdmin))
+            .collect();
+        for auth in &authentications {
+            lines.push(format!(
+                "# Authentication {:?} is enabled, uncomment and replace the placeholder:",
+                auth.login
+            ));
+            lines.push(format!(
+                "# echo '<password>' > {}",
+                shell_quote(&auth.current_password_path().to_string_lossy())
+            ));
+        }
+        for (name, entry) in &self.attributes {
+            let Ok(attribute) = Attribute::attribute(root, name) else {
+                lines.push(format!("# Skipped, not found: {}", name));
+                continue;
+            };
+            let unchanged = attribute.current_value().is_ok_and(|current_value| {
+                ValueChange::diff(&entry.attribute_type, &current_value, &entry.current_value)
+                    .is_none()
+            });
+            if unchanged {
+                continue;
+            }
+            if let Err(err) = attribute.validate_value(&entry.current_value) {
+                lines.push(format!("# Skipped, invalid: {}: {}", name, err));
+                continue;
+            }
+            lines.push(format!(
+                "echo {} > {}",
+                shell_quote(&attribute.format_value(&entry.current_value)),
+                shell_quote(&attribute.current_value_path().to_string_lossy())
+            ));
+        }
+        for auth in &authentications {
+            lines.push("# Logout".to_string());
+            lines.push(format!(
+                "# echo '' > {}",
+                shell_quote(&auth.current_password_path().to_string_lossy())
+            ));
+        }
+        lines.push(String::new());
+        Ok(lines.join("\n"))
+    }
+
+    /// Mismatches of the machine recorded in the profile. Reading the fingerprint walks all
+    /// Attributes, so it is done once per import and passed to the validation and the writes
+    pub fn warnings(&self, root: &Path) -> Vec<String> {
+        let mut warnings = self.fingerprint_warnings(root);
+        warnings.extend(self.authentication_warnings(root));
+        warnings
+    }
+
+    /// Applying a profile built for another model is risky
+    fn fingerprint_warnings(&self, root: &Path) -> Vec<String> {
+        let Some(expected) = self
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.fingerprint.as_ref())
+        else {
+            return Vec::new();
+        };
+        match Fingerprint::read(root) {
+            Ok(actual) => expected
+                .differences(&actual)
+                .into_iter()
+                .map(|difference| format!("PROFILE FROM ANOTHER MACHINE: {}", difference))
+                .collect(),
+            Err(err) => vec![format!("Cannot compute the machine fingerprint: {}", err)],
+        }
+    }
+
+    fn authentication_warnings(&self, root: &Path) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let Some(expected) = &self.authentications else {
+            return warnings;
+        };
+        let actual = match authentication_states(root) {
+            Ok(actual) => actual,
+            Err(err) => {
+                warnings.push(format!("Cannot read authentications: {}", err));
+                return warnings;
+            }
+        };
+        for (name, expected) in expected {
+            match actual.get(name) {
+                None => warnings.push(format!(
+                    "Profile expects authentication {:?} ({}), but it is absent",
+                    name, expected.role
+                )),
+                Some(actual) if actual.is_enabled != expected.is_enabled => warnings.push(format!(
+                    "Profile expects authentication {:?} ({}) to be {}, but it is {}",
+                    name,
+                    expected.role,
+                    enabled_str(expected.is_enabled),
+                    enabled_str(actual.is_enabled)
+                )),
+                _ => {}
+            }
+        }
+        warnings
+    }
+}
+
+impl Display for BatchReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for warning in &self.warnings {
+            writeln!(f, "Warning: {}", warning)?;
+        }
+        for (name, change) in &self.pending {
+            writeln!(f, "To change: {}: {}", name, change)?;
+        }
+        for name in &self.changed {
+            writeln!(f, "Changed: {}", name)?;
+        }
+        for name in &self.skipped {
+            writeln!(f, "Skipped, not found: {}", name)?;
+        }
+        for (name, err) in &self.failed {
+            writeln!(f, "Failed: {}: {}", name, err)?;
+        }
+        if !self.pending.is_empty() {
+            write!(f, "{} to change, ", self.pending.len())?;
+        }
+        write!(
+            f,
+            "{} changed, {} unchanged, {} skipped, {} failed",
+            self.changed.len(),
+            self.unchanged.len(),
+            self.skipped.len(),
+            self.failed.len()
+        )
+    }
+}
+
+/// Exports current and default values of all Attributes of the root, missing directories are created
+#[cfg(feature = "gui")]
+pub fn export_json(root: &Path, path: &Path) -> Result<(), AttributeError> {
+    Profile::read(root, false)?.write(path)
+}
+
+/// Applies a profile written by [export_json], Attributes absent on the root are skipped.
+/// Nothing is written when any value fails validation
+#[cfg(feature = "gui")]
+pub fn import_json(
+    root: &Path,
+    path: &Path,
+    warnings: &[String],
+    knowledge_base: &KnowledgeBase,
+) -> Result<BatchReport, AttributeError> {
+    let profile = Profile::from_file(path)?;
+    let validation = profile.validate_staged(root, warnings);
+    if !validation.failed.is_empty() {
+        let failed: Vec<String> = validation
+            .failed
+            .iter()
+            .map(|(name, err)| format!("{}: {}", name, err))
+            .collect();
+        return Err(AttributeError::InvalidValue(format!(
+            "Import of {:?} aborted, nothing was written. Invalid values: {}",
+            path,
+            failed.join("; ")
+        )));
+    }
+    Ok(profile.apply(root, warnings, knowledge_base))
+}
+
+/// Writes the script of [Profile::script] for the profile file next to it, returns the script path
+#[cfg(feature = "gui")]
+pub fn export_script(root: &Path, path: &Path) -> Result<PathBuf, AttributeError> {
+    let profile = Profile::from_file(path)?;
+    let script_path = path.with_extension("sh");
+    fs::write(&script_path, profile.script(root)?)?;
+    Ok(script_path)
+}
+
+/// Copies raw property files of the Attribute into a new timestamped subdirectory
+pub fn export_raw(attribute: &Attribute, directory: &Path) -> Result<PathBuf, AttributeError> {
+    let directory = directory.join(format!(
+        "{}-{}",
+        attribute.name(),
+        Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    fs::create_dir_all(&directory)?;
+    for (name, content) in raw_properties(attribute.path())? {
+        fs::write(directory.join(name), content)?;
+    }
+    Ok(directory)
+}
+
+fn authentication_states(
+    root: &Path,
+) -> Result<BTreeMap<String, AuthenticationState>, AttributeError> {
+    let mut states = BTreeMap::new();
+    for name in Attribute::authentications_names(root)? {
+        let authentication = Attribute::authentication(root, &name)?;
+        states.insert(
+            name,
+            AuthenticationState {
+                role: authentication.role.as_ref().to_string(),
+                mechanism: authentication.mechanism.as_ref().to_string(),
+                is_enabled: authentication.is_enabled,
+            },
+        );
+    }
+    Ok(states)
+}
+
+fn allowed_values(attribute: &Attribute) -> Option<String> {
+    match attribute {
+        Attribute::Enumeration(attr) if attr.has_possible_values() => {
+            Some(attr.possible_values().join(", "))
+        }
+        Attribute::Integer(attr) => Some(format!(
+            "{}..={} step {}",
+            attr.min_value, attr.max_value, attr.scalar_increment
+        )),
+        Attribute::String(attr) => {
+            Some(format!("length {}..={}", attr.min_length, attr.max_length))
+        }
+        Attribute::OrderedList(attr) if !attr.elements.is_empty() => Some(attr.elements.join(", ")),
+        Attribute::EnumerationList(attr) if !attr.possible_values.is_empty() => {
+            Some(attr.possible_values.join(", "))
+        }
+        _ => None,
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn enabled_str(enabled: bool) -> &'static str {
+    if enabled {
+        "enabled"
+    } else {
+        "disabled"
+    }
+}
+
+pub fn json_schema() -> Value {
+    let entries: Vec<Value> = [
+        (TYPE_ENUMERATION, json!({ "type": "string" })),
+        (TYPE_STRING, json!({ "type": "string" })),
+        (TYPE_RAW, json!({ "type": "string" })),
+        (TYPE_INTEGER, json!({ "type": "integer" })),
+        (
+            TYPE_ORDERED_LIST,
+            json!({ "type": "array", "items": { "type": "string" } }),
+        ),
+        (
+            TYPE_ENUMERATION_LIST,
+            json!({ "type": "array", "items": { "type": "string" }, "uniqueItems": true }),
+        ),
+        (
+            TYPE_MAP,
+            json!({ "type": "object", "additionalProperties": { "type": "string" } }),
+        ),
+    ]
+    .into_iter()
+    .map(|(attribute_type, value)| {
+        json!({
+            "type": "object",
+            "properties": {
+                "type": { "const": attribute_type },
+                "current_value": value,
+                "default_value": value,
+                "_meta": { "type": "object" },
+            },
+            "required": ["type", "current_value"],
+            "additionalProperties": false,
+        })
+    })
+    .collect();
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Firmware Attributes Profile",
+        "type": "object",
+        "properties": {
+            "_meta": { "type": "object" },
+            "pending_reboot": { "type": "boolean" },
+            "authentications": {
+                "type": "object",
+                "additionalProperties": {
+                    "type": "object",
+                    "properties": {
+                        "role": { "type": "string" },
+                        "mechanism": { "type": "string" },
+                        "is_enabled": { "type": "boolean" },
+                    },
+                    "required": ["role", "mechanism", "is_enabled"],
+                },
+            },
+            "attributes": {
+                "type": "object",
+                "additionalProperties": { "oneOf": entries },
+            },
+        },
+        "required": ["attributes"],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "gui")]
+    use crate::test_root::{current_value, TestRoot};
+
+    #[test]
+    #[cfg(feature = "gui")]
+    fn invalid_import_writes_nothing() {
+        let test_root = TestRoot::new("invalid_import_writes_nothing");
+        let root = &test_root.root;
+        for name in ["SecureBoot", "WakeOnLan"] {
+            test_root.attribute(
+                name,
+                &[
+                    ("type", TYPE_ENUMERATION),
+                    ("current_value", "Disabled"),
+                    ("possible_values", "Enabled;Disabled"),
+                ],
+            );
+        }
+        let mut profile = Profile::read(root, false).unwrap();
+        let entries = &mut profile.attributes;
+        entries.get_mut("SecureBoot").unwrap().current_value = "Enabled".to_string().into();
+        entries.get_mut("WakeOnLan").unwrap().current_value = "Sometimes".to_string().into();
+        let path = test_root.directory.join("profile.json");
+        profile.write(&path).unwrap();
+
+        let result = import_json(root, &path, &[], &KnowledgeBase::default());
+        assert!(matches!(result, Err(AttributeError::InvalidValue(_))));
+        assert_eq!(
+            current_value(&root.join("attributes/SecureBoot")),
+            "Disabled\n"
+        );
+    }
+
+    #[test]
+    fn diff_distinguishes_list_changes() {
+        let profile = |boot_order: &str, features: &str| -> Profile {
+            serde_json::from_str(&format!(
+                r#"{{"attributes": {{
+                    "BootOrder": {{"type": "ordered-list", "current_value": {}}},
+                    "Features": {{"type": "enumeration-list", "current_value": {}}}
+                }}}}"#,
+                boot_order, features
+            ))
+            .unwrap()
+        };
+        let old = profile(r#"["USB", "NVMe"]"#, r#"["A", "B"]"#);
+        let changes = |new: &Profile| -> Vec<String> {
+            old.diff(new)
+                .changed
+                .iter()
+                .map(|(name, change)| format!("{}: {}", name, change))
+                .collect()
+        };
+        let new = profile(r#"["NVMe", "USB"]"#, r#"["B", "A"]"#);
+        assert_eq!(
+            changes(&new),
+            ["BootOrder: reordered [USB, NVMe] -> [NVMe, USB]"]
+        );
+
+        let new = profile(r#"["USB", "NVMe"]"#, r#"["A", "C"]"#);
+        assert_eq!(changes(&new), ["Features: added [C], removed [B]"]);
+    }
+}
@@ -1,22 +1,35 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use log::{error, info};
+use log::{error, info, warn};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
 use std::num::ParseIntError;
+use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use std::{fs, io};
 use strum::{AsRefStr, EnumString};
 
 const POSSIBLE_VALUES_DELIMITER: &str = ";";
 const ENUMERATION_VALUES_DELIMITER: &str = ":";
+const ORDERED_LIST_DELIMITERS: &[&str] = &[POSSIBLE_VALUES_DELIMITER, ","];
+const ENUMERATION_LIST_VALUES_DELIMITERS: &[&str] =
+    &[ENUMERATION_VALUES_DELIMITER, POSSIBLE_VALUES_DELIMITER, ","];
+const ENUMERATION_LIST_POSSIBLE_VALUES_DELIMITERS: &[&str] =
+    &[POSSIBLE_VALUES_DELIMITER, ENUMERATION_VALUES_DELIMITER, ","];
+const INDEX_PREFIX_SEPARATORS: &[char] = &[':', '.', ')', '-', ' '];
 const SYSFS_END_LINE: &str = "\n";
+const REDACTED: &str = "<hidden>";
 
-const DEFAULT_INTEGER_MIN_VALUE: i32 = 0;
-const DEFAULT_INTEGER_MAX_VALUE: i32 = i32::MAX;
-const DEFAULT_INTEGER_SCALAR_INCREMENT: i32 = 1;
+const DEFAULT_INTEGER_MIN_VALUE: i64 = 0;
+// Values are 64-bit, but firmware omitting the maximum usually keeps them in 32 bits
+const DEFAULT_INTEGER_MAX_VALUE: i64 = i32::MAX as i64;
+const DEFAULT_INTEGER_SCALAR_INCREMENT: i64 = 1;
 
 const DEFAULT_MIN_STRING_LENGTH: usize = 0;
 const DEFAULT_MAX_STRING_LENGTH: usize = 128;
@@ -27,31 +40,114 @@ const DEFAULT_MAX_PASSWORD_LENGTH: usize = 128;
 const PATH_ATTRIBUTES: &str = "attributes";
 const PATH_AUTHENTICATIONS: &str = "authentication";
 
+/// Known lists without other signs of being a list, like Lenovo "BootOrder" delimited by ':'
 const ENUMERATION_LIST_ATTRIBUTES: &[&str] = &["BootOrder"];
-
-const TYPE_ENUMERATION: &str = "enumeration";
-const TYPE_INTEGER: &str = "integer";
-const TYPE_STRING: &str = "string";
-const TYPE_ORDERED_LIST: &str = "ordered-list";
-
-const TYPE_ENUMERATION_LIST: &str = "enumeration-list";
-
-const PATH_SYSFS_FIRMWARE_ATTRIBUTES: &str = "/sys/class/firmware-attributes/";
+/// Comma separated names of additional enumeration lists
+pub const ENV_ENUMERATION_LIST_ATTRIBUTES: &str = "FW_ATTR_EDITOR_LIST_ATTRIBUTES";
+const MAP_ATTRIBUTES: &[&str] = &[];
+const MAP_ENTRIES_DELIMITER: &str = ";";
+const MAP_KEY_VALUE_DELIMITER: char = '=';
+const EMPTY_LIST_SENTINELS: &[&str] = &["None"];
+const EMPTY_LIST_POLICIES: &[(Vendor, &str, EmptyListPolicy)] =
+    &[(Vendor::Lenovo, "BootOrder", EmptyListPolicy::Refuse)];
+
+pub const TYPE_ENUMERATION: &str = "enumeration";
+pub const TYPE_INTEGER: &str = "integer";
+pub const TYPE_STRING: &str = "string";
+pub const TYPE_ORDERED_LIST: &str = "ordered-list";
+
+pub const TYPE_ENUMERATION_LIST: &str = "enumeration-list";
+/// String Attribute holding `key=value` entries, like `a=1;b=2`
+pub const TYPE_MAP: &str = "map";
+/// Any type unknown to the Editor
+pub const TYPE_RAW: &str = "raw";
+
+pub const PATH_SYSFS_FIRMWARE_ATTRIBUTES: &str = "/sys/class/firmware-attributes/";
+
+const DRIVER_LENOVO: &str = "thinklmi";
+const DRIVER_HP: &str = "hp-bioscfg";
+const DRIVER_DELL: &str = "dell-wmi-sysman";
+
+const PATH_KERNEL_RELEASE: &str = "/proc/sys/kernel/osrelease";
+/// Kernel versions where the firmware attributes drivers got the layout supported by the Editor
+const MIN_KERNEL_VERSIONS: &[(Vendor, (u32, u32))] = &[
+    (Vendor::Dell, (5, 11)),
+    (Vendor::Lenovo, (5, 14)),
+    (Vendor::Hp, (6, 6)),
+];
+
+const CONTAINER_CGROUP_HINTS: &[&str] = &["docker", "lxc", "kubepods", "containerd", "libpod"];
 
 const PROPERTY_CURRENT_VALUE: &str = "current_value";
 const PROPERTY_CURRENT_PASSWORD: &str = "current_password";
+const PROPERTY_SIGNATURE: &str = "signature";
+const PROPERTY_NEW_PASSWORD: &str = "new_password";
 const PROPERTY_DEFAULT_VALUE: &str = "default_value";
 const PROPERTY_DISPLAY_NAME: &str = "display_name";
+const PROPERTY_DISPLAY_NAME_LANGUAGE_CODE: &str = "display_name_language_code";
+const PROPERTY_TYPE: &str = "type";
+const PROPERTY_IS_ENABLED: &str = "is_enabled";
+const PROPERTY_REQUIRES_REBOOT: &str = "requires_reboot";
+const PROPERTY_IMMEDIATE: &str = "immediate";
+const PROPERTY_CATEGORY: &str = "category";
+const PROPERTY_ORDER: &str = "order";
+const PROPERTY_WRITE_ONCE: &str = "write_once";
+const PROPERTY_OTP: &str = "otp";
+const PROPERTY_READ_ONLY: &str = "read_only";
+const PROPERTY_ELEMENTS: &str = "elements";
+const PROPERTY_PASSWORD_MAX_RETRY: &str = "password_max_retry";
 
 #[derive(Debug)]
 pub enum AttributeError {
     MissingFile(PathBuf),
     MissingDirectory(PathBuf),
+    /// File or directory name that is not valid UTF-8
+    NonUtf8Path(PathBuf),
     IOError(io::Error),
     ParseIntError(ParseIntError),
     UnsupportedAttributeType(String),
     VariantNotFount,
     InvalidRoot(PathBuf),
+    InvalidValue(String),
+    WriteRejected {
+        path: PathBuf,
+        errno: Option<i32>,
+        message: String,
+    },
+    /// Failed list write, `restored` tells whether the value is the same as before the write
+    ListWriteFailed {
+        error: Box<AttributeError>,
+        restored: bool,
+    },
+    /// Failed password change, `current_accepted` tells which of the two writes failed
+    PasswordChangeFailed {
+        error: Box<AttributeError>,
+        current_accepted: bool,
+    },
+}
+
+impl AttributeError {
+    /// Underlying error, unwrapping list rollback reports
+    pub fn root_cause(&self) -> &AttributeError {
+        match self {
+            Self::ListWriteFailed { error, .. } => error.root_cause(),
+            Self::PasswordChangeFailed { error, .. } => error.root_cause(),
+            error => error,
+        }
+    }
+
+    fn write_rejected(path: &Path, error: io::Error) -> Self {
+        let message = error.to_string();
+        let message = match message.find(" (os error ") {
+            Some(index) => message[..index].to_string(),
+            None => message,
+        };
+        Self::WriteRejected {
+            path: path.to_path_buf(),
+            errno: error.raw_os_error(),
+            message,
+        }
+    }
 }
 
 impl From<io::Error> for AttributeError {
@@ -74,7 +170,76 @@ impl From<strum::ParseError> for AttributeError {
 
 impl Display for AttributeError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Self::MissingFile(path) => write!(f, "File not found: {:?}", path),
+            Self::MissingDirectory(path) => write!(f, "Directory not found: {:?}", path),
+            Self::NonUtf8Path(path) => write!(f, "Path is not valid UTF-8: {:?}", path),
+            Self::IOError(err) => write!(f, "I/O error: {}", err),
+            Self::ParseIntError(err) => write!(f, "Cannot parse integer: {}", err),
+            Self::UnsupportedAttributeType(attribute_type) => {
+                write!(f, "Unsupported attribute type: {:?}", attribute_type)
+            }
+            Self::VariantNotFount => write!(f, "Unknown variant"),
+            Self::InvalidValue(message) => write!(f, "Invalid value: {}", message),
+            Self::InvalidRoot(path) => {
+                write!(f, "Not a Firmware Attributes root: {:?}", path)
+            }
+            Self::WriteRejected {
+                path,
+                errno,
+                message,
+            } => match errno {
+                Some(errno) => write!(
+                    f,
+                    "Write to {:?} rejected: {} ({})",
+                    path,
+                    message,
+                    errno_name(*errno).map_or(format!("errno {}", errno), str::to_string)
+                ),
+                None => write!(f, "Write to {:?} rejected: {}", path, message),
+            },
+            Self::ListWriteFailed { error, restored } => {
+                if *restored {
+                    write!(f, "{}, the original value is kept", error)
+                } else {
+                    write!(
+                        f,
+                        "{}, the original value could not be restored, check it before reboot",
+                        error
+                    )
+                }
+            }
+            Self::PasswordChangeFailed {
+                error,
+                current_accepted,
+            } => {
+                if *current_accepted {
+                    write!(f, "New password rejected: {}", error)
+                } else {
+                    write!(f, "Current password rejected: {}", error)
+                }
+            }
+        }
+    }
+}
+
+fn errno_name(errno: i32) -> Option<&'static str> {
+    match errno {
+        1 => Some("EPERM"),
+        2 => Some("ENOENT"),
+        5 => Some("EIO"),
+        6 => Some("ENXIO"),
+        12 => Some("ENOMEM"),
+        13 => Some("EACCES"),
+        16 => Some("EBUSY"),
+        19 => Some("ENODEV"),
+        22 => Some("EINVAL"),
+        28 => Some("ENOSPC"),
+        30 => Some("EROFS"),
+        34 => Some("ERANGE"),
+        61 => Some("ENODATA"),
+        95 => Some("EOPNOTSUPP"),
+        _ => None,
     }
 }
 
@@ -93,10 +258,13 @@ pub trait AttributeParser {
         }
     }
 
+    /// Empty for roots without the authentication directory
     fn authentications_names(path: &Path) -> Result<Vec<String>, AttributeError> {
-        if is_firmware_attributes_root(path) {
+        if has_authentications(path) {
             let authentications_path = path.join(PATH_AUTHENTICATIONS);
             directories_names(&authentications_path)
+        } else if is_firmware_attributes_root(path) {
+            Ok(Vec::new())
         } else {
             Err(AttributeError::InvalidRoot(path.to_path_buf()))
         }
@@ -129,29 +297,114 @@ pub trait AttributeParser {
     }
 }
 
+/// Roots are canonicalized, so symlinks to the same directory are listed once, and sorted
 pub fn autodetect_root() -> Vec<PathBuf> {
     let root = PathBuf::from_str(PATH_SYSFS_FIRMWARE_ATTRIBUTES).unwrap();
     let mut list = Vec::new();
     if root.exists() {
-        if is_firmware_attributes_root(&root) {
-            list.push(root.clone());
-        } else {
-            if let Ok(dirs) = root.read_dir() {
-                for dir in dirs {
-                    if let Ok(dir) = dir {
-                        if is_firmware_attributes_root(&dir.path()) {
-                            list.push(dir.path());
-                        }
-                    }
-                }
+        collect_roots(&root, 2, &mut list);
+    }
+    list.sort();
+    list.dedup();
+    list
+}
+
+/// Looks for roots in subdirectories up to the depth
+fn collect_roots(path: &Path, depth: usize, list: &mut Vec<PathBuf>) {
+    if is_firmware_attributes_root(path) {
+        list.push(fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()));
+        return;
+    }
+    if depth == 0 {
+        return;
+    }
+    if let Ok(dirs) = path.read_dir() {
+        for dir in dirs.flatten() {
+            collect_roots(&dir.path(), depth - 1, list);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Vendor {
+    Lenovo,
+    Hp,
+    Dell,
+    Unknown,
+}
+
+pub fn vendor(root: &Path) -> Vendor {
+    match root.file_name().and_then(|name| name.to_str()) {
+        Some(DRIVER_LENOVO) => Vendor::Lenovo,
+        Some(DRIVER_HP) => Vendor::Hp,
+        Some(DRIVER_DELL) => Vendor::Dell,
+        _ => Vendor::Unknown,
+    }
+}
+
+/// Major and minor version of the running kernel
+pub fn kernel_version() -> Option<(u32, u32)> {
+    let release = fs::read_to_string(PATH_KERNEL_RELEASE).ok()?;
+    let mut numbers = release
+        .trim()
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|n| n.parse::<u32>());
+    Some((numbers.next()?.ok()?, numbers.next()?.ok()?))
+}
+
+/// Warning for kernels older than the one that introduced the driver layout of the root
+pub fn kernel_compatibility_warning(root: &Path) -> Option<String> {
+    let vendor = vendor(root);
+    let (_, min_version) = MIN_KERNEL_VERSIONS.iter().find(|(v, _)| *v == vendor)?;
+    let version = kernel_version()?;
+    if version < *min_version {
+        Some(format!(
+            "Kernel {}.{} may be too old for this firmware interface, {}.{} or newer is recommended",
+            version.0, version.1, min_version.0, min_version.1
+        ))
+    } else {
+        None
+    }
+}
+
+pub fn container_environment() -> Option<String> {
+    if Path::new("/.dockerenv").exists() {
+        return Some("a Docker container".to_string());
+    }
+    if Path::new("/run/.containerenv").exists() {
+        return Some("a Podman container".to_string());
+    }
+    if let Ok(container) = std::env::var("container") {
+        return Some(format!("a {} container", container));
+    }
+    if let Ok(cgroup) = fs::read_to_string("/proc/1/cgroup") {
+        for hint in CONTAINER_CGROUP_HINTS {
+            if cgroup.contains(hint) {
+                return Some(format!("a container ({})", hint));
             }
         }
     }
-    list
+    if let (Ok(root), Ok(init_root)) = (fs::metadata("/"), fs::metadata("/proc/1/root/")) {
+        if root.dev() != init_root.dev() || root.ino() != init_root.ino() {
+            return Some("a chroot".to_string());
+        }
+    }
+    None
 }
 
+/// Some minimal drivers expose only the attributes directory
 pub fn is_firmware_attributes_root(root: &Path) -> bool {
-    root.join(PATH_AUTHENTICATIONS).exists() && root.join(PATH_ATTRIBUTES).exists()
+    root.join(PATH_ATTRIBUTES).is_dir()
+}
+
+/// Whether written changes wait for a reboot to be applied by the firmware
+pub fn firmware_attributes_pending_reboot(root: &Path) -> Result<bool, AttributeError> {
+    Attribute::pending_reboot(root)
+}
+
+/// Stricter check for roots exposing the authentication directory too
+pub fn has_authentications(root: &Path) -> bool {
+    is_firmware_attributes_root(root) && root.join(PATH_AUTHENTICATIONS).is_dir()
 }
 
 fn directories_names(path: &Path) -> Result<Vec<String>, AttributeError> {
@@ -160,7 +413,11 @@ fn directories_names(path: &Path) -> Result<Vec<String>, AttributeError> {
         for dir in fs::read_dir(path)? {
             let dir = dir?;
             if dir.metadata()?.is_dir() {
-                result.push(dir.file_name().into_string().unwrap())
+                let name = dir
+                    .file_name()
+                    .into_string()
+                    .map_err(|_| AttributeError::NonUtf8Path(dir.path()))?;
+                result.push(name)
             }
         }
         Ok(result)
@@ -184,12 +441,628 @@ pub trait WriteableAttribute: ReadableAttribute {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Attribute {
     Enumeration(EnumerationAttribute),
     Integer(IntegerAttribute),
     String(StringAttribute),
     OrderedList(OrderedListAttribute),
     EnumerationList(EnumerationListAttribute),
+    Map(MapAttribute),
+    /// Attribute of an unknown type, the value is read and written as is
+    Raw(RawAttribute),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(untagged))]
+pub enum AttributeValue {
+    Integer(i64),
+    String(String),
+    List(Vec<String>),
+    /// Serialized as an object, an empty array would be taken for an empty list
+    #[cfg_attr(feature = "serde", serde(with = "map_entries"))]
+    Map(Vec<(String, String)>),
+}
+
+/// Map entries as an object in the firmware order, arrays of pairs of older exports are accepted
+#[cfg(feature = "serde")]
+mod map_entries {
+    use serde::de::{MapAccess, SeqAccess, Visitor};
+    use serde::ser::SerializeMap;
+    use serde::{Deserializer, Serializer};
+    use std::fmt::Formatter;
+
+    pub fn serialize<S: Serializer>(
+        entries: &[(String, String)],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(entries.len()))?;
+        for (key, value) in entries {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(String, String)>, D::Error> {
+        struct EntriesVisitor;
+
+        impl<'de> Visitor<'de> for EntriesVisitor {
+            type Value = Vec<(String, String)>;
+
+            fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+                formatter.write_str("an object with string values")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(entries)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = seq.next_element()? {
+                    entries.push(entry);
+                }
+                Ok(entries)
+            }
+        }
+
+        deserializer.deserialize_any(EntriesVisitor)
+    }
+}
+
+impl From<i64> for AttributeValue {
+    fn from(value: i64) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<String> for AttributeValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<Vec<String>> for AttributeValue {
+    fn from(value: Vec<String>) -> Self {
+        Self::List(value)
+    }
+}
+
+impl From<Vec<(String, String)>> for AttributeValue {
+    fn from(value: Vec<(String, String)>) -> Self {
+        Self::Map(value)
+    }
+}
+
+impl TryFrom<AttributeValue> for i64 {
+    type Error = AttributeError;
+
+    fn try_from(value: AttributeValue) -> Result<Self, Self::Error> {
+        match value {
+            AttributeValue::Integer(value) => Ok(value),
+            value => Err(AttributeError::InvalidValue(format!(
+                "{:?} is not an integer",
+                value
+            ))),
+        }
+    }
+}
+
+impl TryFrom<AttributeValue> for String {
+    type Error = AttributeError;
+
+    fn try_from(value: AttributeValue) -> Result<Self, Self::Error> {
+        match value {
+            AttributeValue::String(value) => Ok(value),
+            value => Err(AttributeError::InvalidValue(format!(
+                "{:?} is not a string",
+                value
+            ))),
+        }
+    }
+}
+
+impl TryFrom<AttributeValue> for Vec<String> {
+    type Error = AttributeError;
+
+    fn try_from(value: AttributeValue) -> Result<Self, Self::Error> {
+        match value {
+            AttributeValue::List(value) => Ok(value),
+            value => Err(AttributeError::InvalidValue(format!(
+                "{:?} is not a list",
+                value
+            ))),
+        }
+    }
+}
+
+impl TryFrom<AttributeValue> for Vec<(String, String)> {
+    type Error = AttributeError;
+
+    fn try_from(value: AttributeValue) -> Result<Self, Self::Error> {
+        match value {
+            AttributeValue::Map(value) => Ok(value),
+            value => Err(AttributeError::InvalidValue(format!(
+                "{:?} is not a map",
+                value
+            ))),
+        }
+    }
+}
+
+impl Attribute {
+    pub fn current_value(&self) -> Result<AttributeValue, AttributeError> {
+        Ok(match self {
+            Attribute::Enumeration(attr) => AttributeValue::String(attr.current_value()?),
+            Attribute::Integer(attr) => AttributeValue::Integer(attr.current_value()?),
+            Attribute::String(attr) => AttributeValue::String(attr.current_value()?),
+            Attribute::OrderedList(attr) => AttributeValue::List(attr.current_value()?),
+            Attribute::EnumerationList(attr) => AttributeValue::List(attr.current_value()?),
+            Attribute::Map(attr) => AttributeValue::Map(attr.current_value()?),
+            Attribute::Raw(attr) => AttributeValue::String(attr.current_value()?),
+        })
+    }
+
+    pub fn default_value(&self) -> Option<AttributeValue> {
+        match self {
+            Attribute::Enumeration(attr) => attr
+                .common_attribute
+                .default_value
+                .clone()
+                .map(AttributeValue::String),
+            Attribute::Integer(attr) => attr
+                .common_attribute
+                .default_value
+                .map(AttributeValue::Integer),
+            Attribute::String(attr) => attr
+                .common_attribute
+                .default_value
+                .clone()
+                .map(AttributeValue::String),
+            Attribute::OrderedList(attr) => attr
+                .common_attribute
+                .default_value
+                .clone()
+                .map(AttributeValue::List),
+            Attribute::EnumerationList(attr) => attr
+                .common_attribute
+                .default_value
+                .clone()
+                .map(AttributeValue::List),
+            Attribute::Map(attr) => attr
+                .common_attribute
+                .default_value
+                .clone()
+                .map(AttributeValue::Map),
+            Attribute::Raw(attr) => attr
+                .common_attribute
+                .default_value
+                .clone()
+                .map(AttributeValue::String),
+        }
+    }
+
+    pub fn write_value(&self, value: &AttributeValue) -> Result<(), AttributeError> {
+        match (self, value) {
+            (Attribute::Enumeration(attr), AttributeValue::String(value)) => {
+                attr.write_current_value(value)
+            }
+            (Attribute::Integer(attr), AttributeValue::Integer(value)) => {
+                attr.write_current_value(value)
+            }
+            (Attribute::String(attr), AttributeValue::String(value)) => {
+                attr.write_current_value(value)
+            }
+            (Attribute::OrderedList(attr), AttributeValue::List(value)) => {
+                attr.write_current_value(value)
+            }
+            (Attribute::EnumerationList(attr), AttributeValue::List(value)) => {
+                attr.write_current_value(value)
+            }
+            (Attribute::Map(attr), AttributeValue::Map(value)) => attr.write_current_value(value),
+            (Attribute::Raw(attr), AttributeValue::String(value)) => {
+                attr.write_current_value(value)
+            }
+            (attribute, value) => Err(AttributeError::InvalidValue(format!(
+                "{:?} does not match the type of Attribute {:?}",
+                value,
+                attribute.name()
+            ))),
+        }
+    }
+
+    /// Checks the value against the Attribute constraints without writing it
+    pub fn validate_value(&self, value: &AttributeValue) -> Result<(), AttributeError> {
+        let invalid = |message: String| Err(AttributeError::InvalidValue(message));
+        if !self.is_writable() {
+            return invalid(format!("Attribute {:?} is read only", self.name()));
+        }
+        match (self, value) {
+            (Attribute::Enumeration(attr), AttributeValue::String(value))
+                if attr.has_possible_values() && !attr.is_possible_value(value) =>
+            {
+                invalid(format!("{:?} is not a possible value", value))
+            }
+            (Attribute::Enumeration(_), AttributeValue::String(_)) => Ok(()),
+            (Attribute::Integer(attr), AttributeValue::Integer(value)) => {
+                if *value < attr.min_value || *value > attr.max_value {
+                    invalid(format!(
+                        "{} is out of range {}..={}",
+                        value, attr.min_value, attr.max_value
+                    ))
+                } else if attr.scalar_increment > 1
+                    && (*value as i128 - attr.min_value as i128) % attr.scalar_increment as i128
+                        != 0
+                {
+                    invalid(format!(
+                        "{} is not aligned to the increment {}",
+                        value, attr.scalar_increment
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+            (Attribute::String(attr), AttributeValue::String(value)) => {
+                let length = value.chars().count();
+                if length < attr.min_length || length > attr.max_length {
+                    invalid(format!(
+                        "Length {} is out of range {}..={}",
+                        length, attr.min_length, attr.max_length
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+            (Attribute::OrderedList(attr), AttributeValue::List(value)) => {
+                match value
+                    .iter()
+                    .find(|entry| !attr.elements.is_empty() && !attr.elements.contains(entry))
+                {
+                    Some(entry) => invalid(format!("{:?} is not an element of the list", entry)),
+                    None => Ok(()),
+                }
+            }
+            (Attribute::EnumerationList(attr), AttributeValue::List(value)) => {
+                if value.is_empty() && attr.empty_list_policy == EmptyListPolicy::Refuse {
+                    return invalid(format!("Attribute {:?} cannot be cleared", self.name()));
+                }
+                match value.iter().find(|entry| {
+                    !attr.possible_values.is_empty() && !attr.possible_values.contains(entry)
+                }) {
+                    Some(entry) => invalid(format!("{:?} is not a possible value", entry)),
+                    None => Ok(()),
+                }
+            }
+            (Attribute::Map(_), AttributeValue::Map(value)) => {
+                match value.iter().find(|(key, _)| {
+                    key.is_empty()
+                        || key.contains(MAP_KEY_VALUE_DELIMITER)
+                        || key.contains(MAP_ENTRIES_DELIMITER)
+                }) {
+                    Some((key, _)) => invalid(format!("{:?} is not a valid key", key)),
+                    None => Ok(()),
+                }
+            }
+            (Attribute::Raw(_), AttributeValue::String(_)) => Ok(()),
+            (attribute, value) => invalid(format!(
+                "{:?} does not match the type of Attribute {:?}",
+                value,
+                attribute.name()
+            )),
+        }
+    }
+
+    pub fn is_writable(&self) -> bool {
+        match self {
+            Attribute::Enumeration(attr) => attr.common_attribute.is_writable(),
+            Attribute::Integer(attr) => attr.common_attribute.is_writable(),
+            Attribute::String(attr) => attr.common_attribute.is_writable(),
+            Attribute::OrderedList(attr) => attr.common_attribute.is_writable(),
+            Attribute::EnumerationList(attr) => attr.common_attribute.is_writable(),
+            Attribute::Map(attr) => attr.common_attribute.is_writable(),
+            Attribute::Raw(attr) => attr.common_attribute.is_writable(),
+        }
+    }
+
+    pub fn parse_value(&self, value: &str) -> Result<AttributeValue, AttributeError> {
+        Ok(match self {
+            Attribute::Integer(_) => AttributeValue::Integer(i64::from_str(value)?),
+            Attribute::Enumeration(_) | Attribute::String(_) => {
+                AttributeValue::String(value.to_string())
+            }
+            Attribute::OrderedList(attr) => AttributeValue::List(attr.parse_list(value)),
+            Attribute::EnumerationList(attr) => AttributeValue::List(attr.parse_list(value)),
+            Attribute::Map(_) => AttributeValue::Map(parse_map(value)),
+            Attribute::Raw(_) => AttributeValue::String(value.to_string()),
+        })
+    }
+
+    pub fn format_value(&self, value: &AttributeValue) -> String {
+        match (self, value) {
+            (_, AttributeValue::Integer(value)) => value.to_string(),
+            (_, AttributeValue::String(value)) => value.clone(),
+            (Attribute::OrderedList(attr), AttributeValue::List(value)) => attr.format_list(value),
+            (Attribute::EnumerationList(attr), AttributeValue::List(value)) => {
+                attr.format_list(value)
+            }
+            (_, AttributeValue::List(value)) => value.join(POSSIBLE_VALUES_DELIMITER),
+            (Attribute::Map(attr), AttributeValue::Map(value)) => attr.format_map(value),
+            (_, AttributeValue::Map(value)) => format_map(value, false),
+        }
+    }
+
+    pub fn attribute_type(&self) -> &'static str {
+        match self {
+            Attribute::Enumeration(_) => TYPE_ENUMERATION,
+            Attribute::Integer(_) => TYPE_INTEGER,
+            Attribute::String(_) => TYPE_STRING,
+            Attribute::OrderedList(_) => TYPE_ORDERED_LIST,
+            Attribute::EnumerationList(_) => TYPE_ENUMERATION_LIST,
+            Attribute::Map(_) => TYPE_MAP,
+            Attribute::Raw(_) => TYPE_RAW,
+        }
+    }
+
+    pub fn raw_type(&self) -> &String {
+        match self {
+            Attribute::Enumeration(attr) => &attr.common_attribute.raw_type,
+            Attribute::Integer(attr) => &attr.common_attribute.raw_type,
+            Attribute::String(attr) => &attr.common_attribute.raw_type,
+            Attribute::OrderedList(attr) => &attr.common_attribute.raw_type,
+            Attribute::EnumerationList(attr) => &attr.common_attribute.raw_type,
+            Attribute::Map(attr) => &attr.common_attribute.raw_type,
+            Attribute::Raw(attr) => &attr.common_attribute.raw_type,
+        }
+    }
+
+    /// Unparsable values are not cached, so the error is reported on read
+    fn seed_current_value(&self, raw: &str) {
+        let Ok(value) = self.parse_value(raw) else {
+            return;
+        };
+        match (self, value) {
+            (Attribute::Enumeration(attr), AttributeValue::String(value)) => {
+                attr.common_attribute.seed_current_value_cache(value)
+            }
+            (Attribute::String(attr), AttributeValue::String(value)) => {
+                attr.common_attribute.seed_current_value_cache(value)
+            }
+            (Attribute::Integer(attr), AttributeValue::Integer(value)) => {
+                attr.common_attribute.seed_current_value_cache(value)
+            }
+            (Attribute::OrderedList(attr), AttributeValue::List(value)) => {
+                attr.common_attribute.seed_current_value_cache(value)
+            }
+            (Attribute::EnumerationList(attr), AttributeValue::List(value)) => {
+                attr.common_attribute.seed_current_value_cache(value)
+            }
+            (Attribute::Map(attr), AttributeValue::Map(value)) => {
+                attr.common_attribute.seed_current_value_cache(value)
+            }
+            (Attribute::Raw(attr), AttributeValue::String(value)) => {
+                attr.common_attribute.seed_current_value_cache(value)
+            }
+            _ => {}
+        }
+    }
+
+    /// Cached current value, the firmware is not read
+    pub fn cached_current_value(&self) -> Option<AttributeValue> {
+        match self {
+            Attribute::Enumeration(attr) => {
+                attr.common_attribute.cached_current_value().map(Into::into)
+            }
+            Attribute::Integer(attr) => {
+                attr.common_attribute.cached_current_value().map(Into::into)
+            }
+            Attribute::String(attr) => attr.common_attribute.cached_current_value().map(Into::into),
+            Attribute::OrderedList(attr) => {
+                attr.common_attribute.cached_current_value().map(Into::into)
+            }
+            Attribute::EnumerationList(attr) => {
+                attr.common_attribute.cached_current_value().map(Into::into)
+            }
+            Attribute::Map(attr) => attr.common_attribute.cached_current_value().map(Into::into),
+            Attribute::Raw(attr) => attr.common_attribute.cached_current_value().map(Into::into),
+        }
+    }
+
+    /// Reads the current value bypassing the cache, returns it when it differs from the cached one
+    pub fn external_change(&self) -> Result<Option<AttributeValue>, AttributeError> {
+        let Some(cached) = self.cached_current_value() else {
+            return Ok(None);
+        };
+        let fresh = self.parse_value(&read_attribute_property(
+            self.path(),
+            PROPERTY_CURRENT_VALUE,
+        )?)?;
+        Ok((fresh != cached).then_some(fresh))
+    }
+
+    pub fn is_current_value_cached(&self) -> bool {
+        match self {
+            Attribute::Enumeration(attr) => attr.common_attribute.is_current_value_cached(),
+            Attribute::Integer(attr) => attr.common_attribute.is_current_value_cached(),
+            Attribute::String(attr) => attr.common_attribute.is_current_value_cached(),
+            Attribute::OrderedList(attr) => attr.common_attribute.is_current_value_cached(),
+            Attribute::EnumerationList(attr) => attr.common_attribute.is_current_value_cached(),
+            Attribute::Map(attr) => attr.common_attribute.is_current_value_cached(),
+            Attribute::Raw(attr) => attr.common_attribute.is_current_value_cached(),
+        }
+    }
+
+    pub fn clear_current_value_cache(&self) {
+        match self {
+            Attribute::Enumeration(attr) => attr.common_attribute.clear_current_value_cache(),
+            Attribute::Integer(attr) => attr.common_attribute.clear_current_value_cache(),
+            Attribute::String(attr) => attr.common_attribute.clear_current_value_cache(),
+            Attribute::OrderedList(attr) => attr.common_attribute.clear_current_value_cache(),
+            Attribute::EnumerationList(attr) => attr.common_attribute.clear_current_value_cache(),
+            Attribute::Map(attr) => attr.common_attribute.clear_current_value_cache(),
+            Attribute::Raw(attr) => attr.common_attribute.clear_current_value_cache(),
+        }
+    }
+
+    pub fn category(&self) -> Option<&String> {
+        match self {
+            Attribute::Enumeration(attr) => attr.common_attribute.category.as_ref(),
+            Attribute::Integer(attr) => attr.common_attribute.category.as_ref(),
+            Attribute::String(attr) => attr.common_attribute.category.as_ref(),
+            Attribute::OrderedList(attr) => attr.common_attribute.category.as_ref(),
+            Attribute::EnumerationList(attr) => attr.common_attribute.category.as_ref(),
+            Attribute::Map(attr) => attr.common_attribute.category.as_ref(),
+            Attribute::Raw(attr) => attr.common_attribute.category.as_ref(),
+        }
+    }
+
+    pub fn order(&self) -> Option<i32> {
+        match self {
+            Attribute::Enumeration(attr) => attr.common_attribute.order,
+            Attribute::Integer(attr) => attr.common_attribute.order,
+            Attribute::String(attr) => attr.common_attribute.order,
+            Attribute::OrderedList(attr) => attr.common_attribute.order,
+            Attribute::EnumerationList(attr) => attr.common_attribute.order,
+            Attribute::Map(attr) => attr.common_attribute.order,
+            Attribute::Raw(attr) => attr.common_attribute.order,
+        }
+    }
+
+    pub fn write_once(&self) -> bool {
+        match self {
+            Attribute::Enumeration(attr) => attr.common_attribute.write_once,
+            Attribute::Integer(attr) => attr.common_attribute.write_once,
+            Attribute::String(attr) => attr.common_attribute.write_once,
+            Attribute::OrderedList(attr) => attr.common_attribute.write_once,
+            Attribute::EnumerationList(attr) => attr.common_attribute.write_once,
+            Attribute::Map(attr) => attr.common_attribute.write_once,
+            Attribute::Raw(attr) => attr.common_attribute.write_once,
+        }
+    }
+
+    /// Write-once Attribute that doesn't hold the default value anymore
+    pub fn is_programmed(&self) -> bool {
+        self.write_once()
+            && matches!(
+                (self.current_value(), self.default_value()),
+                (Ok(current_value), Some(default_value)) if current_value != default_value
+            )
+    }
+
+    pub fn requires_reboot(&self) -> Option<bool> {
+        match self {
+            Attribute::Enumeration(attr) => attr.common_attribute.requires_reboot,
+            Attribute::Integer(attr) => attr.common_attribute.requires_reboot,
+            Attribute::String(attr) => attr.common_attribute.requires_reboot,
+            Attribute::OrderedList(attr) => attr.common_attribute.requires_reboot,
+            Attribute::EnumerationList(attr) => attr.common_attribute.requires_reboot,
+            Attribute::Map(attr) => attr.common_attribute.requires_reboot,
+            Attribute::Raw(attr) => attr.common_attribute.requires_reboot,
+        }
+    }
+
+    /// The firmware reports another type than the one the Attribute was parsed with,
+    /// a missing or unreadable type file is not a change
+    pub fn is_type_changed(&self) -> bool {
+        matches!(
+            try_read_attribute_property(self.path(), PROPERTY_TYPE),
+            Ok(Some(raw_type)) if &raw_type != self.raw_type()
+        )
+    }
+
+    pub fn type_description(&self) -> String {
+        let attribute_type = self.attribute_type();
+        let raw_type = self.raw_type();
+        if raw_type == attribute_type {
+            raw_type.clone()
+        } else {
+            format!("{} (treated as {})", raw_type, attribute_type)
+        }
+    }
+
+    pub fn name(&self) -> &String {
+        match self {
+            Attribute::Enumeration(attr) => &attr.common_attribute.name,
+            Attribute::Integer(attr) => &attr.common_attribute.name,
+            Attribute::String(attr) => &attr.common_attribute.name,
+            Attribute::OrderedList(attr) => &attr.common_attribute.name,
+            Attribute::EnumerationList(attr) => &attr.common_attribute.name,
+            Attribute::Map(attr) => &attr.common_attribute.name,
+            Attribute::Raw(attr) => &attr.common_attribute.name,
+        }
+    }
+
+    pub fn display_name(&self) -> &String {
+        match self {
+            Attribute::Enumeration(attr) => attr.common_attribute.display_name(),
+            Attribute::Integer(attr) => attr.common_attribute.display_name(),
+            Attribute::String(attr) => attr.common_attribute.display_name(),
+            Attribute::OrderedList(attr) => attr.common_attribute.display_name(),
+            Attribute::EnumerationList(attr) => attr.common_attribute.display_name(),
+            Attribute::Map(attr) => attr.common_attribute.display_name(),
+            Attribute::Raw(attr) => attr.common_attribute.display_name(),
+        }
+    }
+
+    /// Language codes with a localized display name
+    pub fn display_languages(&self) -> Vec<&String> {
+        let display_names = match self {
+            Attribute::Enumeration(attr) => &attr.common_attribute.localized_display_names,
+            Attribute::Integer(attr) => &attr.common_attribute.localized_display_names,
+            Attribute::String(attr) => &attr.common_attribute.localized_display_names,
+            Attribute::OrderedList(attr) => &attr.common_attribute.localized_display_names,
+            Attribute::EnumerationList(attr) => &attr.common_attribute.localized_display_names,
+            Attribute::Map(attr) => &attr.common_attribute.localized_display_names,
+            Attribute::Raw(attr) => &attr.common_attribute.localized_display_names,
+        };
+        display_names.iter().map(|(code, _)| code).collect()
+    }
+
+    pub fn set_cache_ttl(&mut self, ttl: Option<Duration>) {
+        match self {
+            Attribute::Enumeration(attr) => attr.common_attribute.cache_ttl = ttl,
+            Attribute::Integer(attr) => attr.common_attribute.cache_ttl = ttl,
+            Attribute::String(attr) => attr.common_attribute.cache_ttl = ttl,
+            Attribute::OrderedList(attr) => attr.common_attribute.cache_ttl = ttl,
+            Attribute::EnumerationList(attr) => attr.common_attribute.cache_ttl = ttl,
+            Attribute::Map(attr) => attr.common_attribute.cache_ttl = ttl,
+            Attribute::Raw(attr) => attr.common_attribute.cache_ttl = ttl,
+        }
+    }
+
+    /// `display_name()` falls back to the name when the language is not available
+    pub fn set_display_language(&mut self, language: Option<String>) {
+        match self {
+            Attribute::Enumeration(attr) => attr.common_attribute.display_language = language,
+            Attribute::Integer(attr) => attr.common_attribute.display_language = language,
+            Attribute::String(attr) => attr.common_attribute.display_language = language,
+            Attribute::OrderedList(attr) => attr.common_attribute.display_language = language,
+            Attribute::EnumerationList(attr) => attr.common_attribute.display_language = language,
+            Attribute::Map(attr) => attr.common_attribute.display_language = language,
+            Attribute::Raw(attr) => attr.common_attribute.display_language = language,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        match self {
+            Attribute::Enumeration(attr) => &attr.common_attribute.path,
+            Attribute::Integer(attr) => &attr.common_attribute.path,
+            Attribute::String(attr) => &attr.common_attribute.path,
+            Attribute::OrderedList(attr) => &attr.common_attribute.path,
+            Attribute::EnumerationList(attr) => &attr.common_attribute.path,
+            Attribute::Map(attr) => &attr.common_attribute.path,
+            Attribute::Raw(attr) => &attr.common_attribute.path,
+        }
+    }
+
+    pub fn current_value_path(&self) -> PathBuf {
+        self.path().join(PROPERTY_CURRENT_VALUE)
+    }
 }
 
 impl AttributeParser for Attribute {
@@ -202,98 +1075,272 @@ impl TryFrom<PathBuf> for Attribute {
 
     fn try_from(value: PathBuf) -> Result<Self, Self::Error> {
         if value.exists() && value.is_dir() {
-            match attribute_type(&value)?.as_str() {
-                TYPE_ENUMERATION => Ok(Self::Enumeration(value.try_into()?)),
-                TYPE_INTEGER => Ok(Self::Integer(value.try_into()?)),
-                TYPE_STRING => Ok(Self::String(value.try_into()?)),
-                TYPE_ORDERED_LIST => Ok(Self::OrderedList(value.try_into()?)),
-                TYPE_ENUMERATION_LIST => Ok(Self::EnumerationList(value.try_into()?)),
-                attribute_type => Err(AttributeError::UnsupportedAttributeType(
-                    attribute_type.to_string(),
-                )),
-            }
+            (&AttributeProperties::lazy(value)).try_into()
         } else {
             Err(AttributeError::MissingDirectory(value.to_path_buf()))
         }
     }
 }
 
+impl TryFrom<&AttributeProperties> for Attribute {
+    type Error = AttributeError;
+
+    fn try_from(value: &AttributeProperties) -> Result<Self, Self::Error> {
+        let attribute = match attribute_type(value)?.as_str() {
+            TYPE_ENUMERATION => Self::Enumeration(value.try_into()?),
+            TYPE_INTEGER => Self::Integer(value.try_into()?),
+            TYPE_STRING => Self::String(value.try_into()?),
+            TYPE_ORDERED_LIST => Self::OrderedList(value.try_into()?),
+            TYPE_ENUMERATION_LIST => Self::EnumerationList(value.try_into()?),
+            TYPE_MAP => Self::Map(value.try_into()?),
+            // Shown as a plain text, so the value can still be inspected and edited manually
+            _ => Self::Raw(value.try_into()?),
+        };
+        if let Some(current_value) = value.preloaded(PROPERTY_CURRENT_VALUE) {
+            attribute.seed_current_value(current_value);
+        }
+        Ok(attribute)
+    }
+}
+
+/// Attributes by name, Attributes that could not be read keep the error
+pub type LoadedAttributes = Vec<(String, Result<Attribute, AttributeError>)>;
+
+/// Reads all Attributes of the root walking each Attribute directory only once.
+/// Current values are read along with the other properties and cached.
+pub fn read_attributes(root: &Path) -> Result<LoadedAttributes, AttributeError> {
+    read_attributes_with_progress(root, |_, _| {})
+}
+
+/// Same as `read_attributes`, `progress` receives the number of read and of all Attributes
+pub fn read_attributes_with_progress(
+    root: &Path,
+    mut progress: impl FnMut(usize, usize),
+) -> Result<LoadedAttributes, AttributeError> {
+    let names = Attribute::attributes_names(root)?;
+    let total = names.len();
+    let mut attributes = Vec::with_capacity(total);
+    for (index, name) in names.into_iter().enumerate() {
+        let attribute = AttributeProperties::preload(root.join(PATH_ATTRIBUTES).join(&name))
+            .and_then(|properties| Attribute::try_from(&properties));
+        attributes.push((name, attribute));
+        progress(index + 1, total);
+    }
+    Ok(attributes)
+}
+
+/// Property files of a single Attribute directory, read on demand or in advance
+pub struct AttributeProperties {
+    path: PathBuf,
+    /// `None` value means that the file is listed, but could not be read in advance
+    preloaded: Option<HashMap<String, Option<String>>>,
+}
+
+impl AttributeProperties {
+    fn lazy(path: PathBuf) -> Self {
+        Self {
+            path,
+            preloaded: None,
+        }
+    }
+
+    fn preload(path: PathBuf) -> Result<Self, AttributeError> {
+        let mut preloaded = HashMap::new();
+        for entry in fs::read_dir(&path)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                let value = fs::read_to_string(entry.path())
+                    .ok()
+                    .map(|s| s.trim_end_matches(SYSFS_END_LINE).to_string());
+                preloaded.insert(entry.file_name().to_string_lossy().to_string(), value);
+            }
+        }
+        Ok(Self {
+            path,
+            preloaded: Some(preloaded),
+        })
+    }
+
+    fn preloaded(&self, property: &str) -> Option<&String> {
+        self.preloaded.as_ref()?.get(property)?.as_ref()
+    }
+
+    fn read(&self, property: &str) -> Result<String, AttributeError> {
+        match &self.preloaded {
+            Some(preloaded) if !preloaded.contains_key(property) => {
+                Err(AttributeError::MissingFile(self.path.join(property)))
+            }
+            _ => match self.preloaded(property) {
+                Some(value) => Ok(value.clone()),
+                None => read_attribute_property(&self.path, property),
+            },
+        }
+    }
+
+    fn try_read(&self, property: &str) -> Result<Option<String>, AttributeError> {
+        match &self.preloaded {
+            Some(preloaded) if !preloaded.contains_key(property) => Ok(None),
+            _ => match self.preloaded(property) {
+                Some(value) => Ok(Some(value.clone())),
+                None => try_read_attribute_property(&self.path, property),
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CommonAttribute<T = String> {
     pub path: PathBuf,
     pub name: String,
     pub default_value: Option<T>,
     pub display_name: Option<String>,
     pub display_name_language_code: Option<String>,
-
-    current_value_cache: Arc<Mutex<Option<T>>>,
+    /// Display names by language code, from `display_name_<code>` files
+    pub localized_display_names: Vec<(String, String)>,
+    /// Language of `display_name()`, the firmware default when `None`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub display_language: Option<String>,
+    pub raw_type: String,
+    /// Optional firmware hint, `None` when the firmware doesn't tell
+    pub requires_reboot: Option<bool>,
+    /// Grouping and position as in the BIOS setup screen, if the driver exposes them
+    pub category: Option<String>,
+    pub order: Option<i32>,
+    /// One-time programmable Attribute, can be written only once
+    pub write_once: bool,
+    /// Read-only by the firmware flag or by the permissions of the current value file
+    pub read_only: bool,
+    /// Age after which the cached current value is read again, kept until a write when `None`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub cache_ttl: Option<Duration>,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    current_value_cache: Arc<Mutex<Option<(T, Instant)>>>,
 }
 
-impl TryFrom<PathBuf> for CommonAttribute {
+impl TryFrom<&AttributeProperties> for CommonAttribute {
     type Error = AttributeError;
 
-    fn try_from(path: PathBuf) -> Result<Self, Self::Error> {
-        Ok(Self {
-            name: attribute_name(&path),
-            default_value: try_read_attribute_property(&path, PROPERTY_DEFAULT_VALUE)?,
-            display_name: try_read_attribute_property(&path, PROPERTY_DISPLAY_NAME)?,
-            display_name_language_code: try_read_attribute_property(
-                &path,
-                "display_name_language_code",
-            )?,
-            path,
-            current_value_cache: Arc::new(Mutex::default()),
-        })
+    fn try_from(properties: &AttributeProperties) -> Result<Self, Self::Error> {
+        let default_value = properties.try_read(PROPERTY_DEFAULT_VALUE)?;
+        Self::with_default_value(properties, default_value)
     }
 }
 
-impl TryFrom<PathBuf> for CommonAttribute<Vec<String>> {
+impl TryFrom<&AttributeProperties> for CommonAttribute<Vec<String>> {
     type Error = AttributeError;
 
-    fn try_from(path: PathBuf) -> Result<Self, Self::Error> {
-        Ok(Self {
-            name: attribute_name(&path),
-            default_value: try_read_attribute_property(&path, PROPERTY_DEFAULT_VALUE)?.map(|s| {
-                s.split(POSSIBLE_VALUES_DELIMITER)
-                    .map(|s| s.to_string())
-                    .collect()
-            }),
-            display_name: try_read_attribute_property(&path, PROPERTY_DISPLAY_NAME)?,
-            display_name_language_code: try_read_attribute_property(
-                &path,
-                "display_name_language_code",
-            )?,
-            path,
-            current_value_cache: Arc::new(Mutex::default()),
-        })
+    fn try_from(properties: &AttributeProperties) -> Result<Self, Self::Error> {
+        let default_value = properties
+            .try_read(PROPERTY_DEFAULT_VALUE)?
+            .map(|s| split_list(&s, POSSIBLE_VALUES_DELIMITER));
+        Self::with_default_value(properties, default_value)
     }
 }
 
-impl TryFrom<PathBuf> for CommonAttribute<i32> {
+impl TryFrom<&AttributeProperties> for CommonAttribute<i64> {
     type Error = AttributeError;
 
-    fn try_from(value: PathBuf) -> Result<Self, Self::Error> {
-        let path = value.to_path_buf();
-        let default_value = try_read_attribute_property(&path, PROPERTY_DEFAULT_VALUE)?;
+    fn try_from(properties: &AttributeProperties) -> Result<Self, Self::Error> {
+        let default_value = properties
+            .try_read(PROPERTY_DEFAULT_VALUE)?
+            .map(|v| i64::from_str(v.as_str()))
+            .transpose()?;
+        Self::with_default_value(properties, default_value)
+    }
+}
+
+/// The plain `display_name` belongs to the first listed language when there is no separate file
+fn localized_display_names(
+    properties: &AttributeProperties,
+    language_codes: Option<&str>,
+    display_name: Option<&String>,
+) -> Result<Vec<(String, String)>, AttributeError> {
+    let mut display_names = Vec::new();
+    for (index, code) in language_codes
+        .map(|codes| split_list(codes.trim(), POSSIBLE_VALUES_DELIMITER))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|code| code.trim().to_string())
+        .enumerate()
+    {
+        let localized = properties.try_read(&format!("{}_{}", PROPERTY_DISPLAY_NAME, code))?;
+        if let Some(localized) = localized.or_else(|| display_name.filter(|_| index == 0).cloned())
+        {
+            display_names.push((code, localized));
+        }
+    }
+    Ok(display_names)
+}
+
+impl<T> CommonAttribute<T> {
+    fn with_default_value(
+        properties: &AttributeProperties,
+        default_value: Option<T>,
+    ) -> Result<Self, AttributeError> {
+        let display_name = properties.try_read(PROPERTY_DISPLAY_NAME)?;
+        let display_name_language_code =
+            properties.try_read(PROPERTY_DISPLAY_NAME_LANGUAGE_CODE)?;
+        let localized_display_names = localized_display_names(
+            properties,
+            display_name_language_code.as_deref(),
+            display_name.as_ref(),
+        )?;
         Ok(Self {
-            name: attribute_name(&path),
-            default_value: default_value
-                .map(|v| i32::from_str(v.as_str()))
-                .transpose()?,
-            display_name: try_read_attribute_property(&path, PROPERTY_DISPLAY_NAME)?,
-            display_name_language_code: try_read_attribute_property(
-                &path,
-                "display_name_language_code",
-            )?,
-            path,
+            name: attribute_name(&properties.path)?,
+            default_value,
+            display_name,
+            display_name_language_code,
+            localized_display_names,
+            display_language: None,
+            raw_type: properties.try_read(PROPERTY_TYPE)?.unwrap_or_default(),
+            requires_reboot: requires_reboot(properties)?,
+            category: properties
+                .try_read(PROPERTY_CATEGORY)?
+                .filter(|category| !category.is_empty()),
+            order: properties
+                .try_read(PROPERTY_ORDER)?
+                .and_then(|order| i32::from_str(order.trim()).ok()),
+            write_once: [PROPERTY_WRITE_ONCE, PROPERTY_OTP]
+                .iter()
+                .map(|property| properties.try_read(property))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten()
+                .any(|value| value == "1"),
+            read_only: properties
+                .try_read(PROPERTY_READ_ONLY)?
+                .is_some_and(|value| value == "1")
+                || fs::metadata(properties.path.join(PROPERTY_CURRENT_VALUE))
+                    .is_ok_and(|metadata| metadata.mode() & 0o222 == 0),
+            path: properties.path.clone(),
+            cache_ttl: None,
             current_value_cache: Arc::new(Mutex::default()),
         })
     }
-}
 
-impl<T> CommonAttribute<T> {
     pub fn display_name(&self) -> &String {
-        self.display_name.as_ref().unwrap_or(&self.name)
+        match &self.display_language {
+            Some(language) => self
+                .localized_display_names
+                .iter()
+                .find(|(code, _)| code == language)
+                .map_or(&self.name, |(_, display_name)| display_name),
+            None => self.display_name.as_ref().unwrap_or(&self.name),
+        }
+    }
+
+    pub fn is_writable(&self) -> bool {
+        !self.read_only
+    }
+
+    pub fn root(&self) -> Option<&Path> {
+        self.path.parent()?.parent()
+    }
+
+    pub fn vendor(&self) -> Vendor {
+        self.root().map_or(Vendor::Unknown, vendor)
     }
 }
 
@@ -303,37 +1350,124 @@ impl<T: Clone> CommonAttribute<T> {
         f: F,
     ) -> Result<T, AttributeError> {
         let mut lock = self.current_value_cache.lock().unwrap();
-        if let Some(cache) = lock.as_ref() {
-            Ok(cache.clone())
-        } else {
-            let value = f()?;
-            lock.replace(value.clone());
-            Ok(value)
+        match lock.as_ref() {
+            Some((cache, read_at)) if !self.is_expired(read_at) => Ok(cache.clone()),
+            _ => {
+                let value = f()?;
+                lock.replace((value.clone(), Instant::now()));
+                Ok(value)
+            }
         }
     }
 
-    fn clear_current_value_cache(&self) {
+    fn is_expired(&self, read_at: &Instant) -> bool {
+        self.cache_ttl.is_some_and(|ttl| read_at.elapsed() >= ttl)
+    }
+
+    fn cached_current_value(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.current_value_cache
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|(value, _)| value.clone())
+    }
+
+    pub fn is_current_value_cached(&self) -> bool {
+        self.current_value_cache
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|(_, read_at)| !self.is_expired(read_at))
+    }
+
+    pub fn clear_current_value_cache(&self) {
         self.current_value_cache.lock().unwrap().take();
     }
+
+    fn seed_current_value_cache(&self, value: T) {
+        self.current_value_cache
+            .lock()
+            .unwrap()
+            .replace((value, Instant::now()));
+    }
 }
 
-fn attribute_name(root: &Path) -> String {
-    root.file_name().unwrap().to_str().unwrap().to_string()
+fn attribute_name(root: &Path) -> Result<String, AttributeError> {
+    root.file_name()
+        .and_then(|name| name.to_str())
+        .map(str::to_string)
+        .ok_or_else(|| AttributeError::NonUtf8Path(root.to_path_buf()))
 }
 
-fn attribute_type(root: &Path) -> Result<String, AttributeError> {
-    let attribute_name = attribute_name(root);
-    let attribute_type = read_attribute_property(&root, "type")?;
+fn attribute_type(properties: &AttributeProperties) -> Result<String, AttributeError> {
+    let attribute_name = attribute_name(&properties.path)?;
+    let attribute_type = properties.read(PROPERTY_TYPE)?;
 
-    if attribute_type == TYPE_ENUMERATION
-        && ENUMERATION_LIST_ATTRIBUTES.contains(&attribute_name.as_str())
-    {
+    if attribute_type == TYPE_ENUMERATION && is_enumeration_list(&attribute_name, properties)? {
         Ok(TYPE_ENUMERATION_LIST.to_string())
+    } else if attribute_type == TYPE_STRING
+        && (MAP_ATTRIBUTES.contains(&attribute_name.as_str())
+            || properties
+                .try_read(PROPERTY_CURRENT_VALUE)?
+                .is_some_and(|value| is_map(&value)))
+    {
+        Ok(TYPE_MAP.to_string())
     } else {
         Ok(attribute_type)
     }
 }
 
+/// A single possible value cannot contain the possible values delimiter,
+/// so such a current value holds several of them
+fn is_enumeration_list(
+    attribute_name: &str,
+    properties: &AttributeProperties,
+) -> Result<bool, AttributeError> {
+    let overridden = std::env::var(ENV_ENUMERATION_LIST_ATTRIBUTES)
+        .is_ok_and(|names| names.split(',').any(|name| name.trim() == attribute_name));
+    Ok(overridden
+        || ENUMERATION_LIST_ATTRIBUTES.contains(&attribute_name)
+        || properties.try_read(PROPERTY_ELEMENTS)?.is_some()
+        || properties
+            .try_read(PROPERTY_CURRENT_VALUE)?
+            .is_some_and(|value| value.contains(POSSIBLE_VALUES_DELIMITER)))
+}
+
+/// Exact contents of every property file of the Attribute, without trimming.
+/// Password properties are redacted and unreadable files are skipped.
+pub fn raw_properties(path: &Path) -> Result<Vec<(String, Vec<u8>)>, AttributeError> {
+    let mut properties = Vec::new();
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.contains("password") {
+            properties.push((name, REDACTED.as_bytes().to_vec()));
+            continue;
+        }
+        match fs::read(entry.path()) {
+            Ok(content) => properties.push((name, content)),
+            Err(err) => info!("Property {:?} skipped: {}", entry.path(), err),
+        }
+    }
+    properties.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(properties)
+}
+
+fn requires_reboot(properties: &AttributeProperties) -> Result<Option<bool>, AttributeError> {
+    Ok(match properties.try_read(PROPERTY_REQUIRES_REBOOT)? {
+        Some(value) => Some(value == "1"),
+        None => properties
+            .try_read(PROPERTY_IMMEDIATE)?
+            .map(|value| value != "1"),
+    })
+}
+
 fn read_attribute_property(root: &Path, property: &str) -> Result<String, AttributeError> {
     let path = root.join(property);
     if path.exists() {
@@ -365,6 +1499,16 @@ fn try_read_attribute_property(
     })
 }
 
+/// Returns the first candidate delimiter found in any of the raw values,
+/// or the first candidate when none of them is present.
+fn detect_delimiter<'a>(raw_values: &[Option<&str>], candidates: &[&'a str]) -> &'a str {
+    raw_values
+        .iter()
+        .flatten()
+        .find_map(|raw| candidates.iter().find(|d| raw.contains(**d)))
+        .unwrap_or(&candidates[0])
+}
+
 fn write_attribute_property(
     root: &Path,
     property: &str,
@@ -373,7 +1517,7 @@ fn write_attribute_property(
     let path = root.join(property);
     if path.exists() {
         let printable_value = if path.ends_with(PROPERTY_CURRENT_PASSWORD) {
-            "<hidden>"
+            REDACTED
         } else {
             value
         };
@@ -381,7 +1525,7 @@ fn write_attribute_property(
             "Write attribute path {:?} property {} value {}",
             path, property, printable_value
         );
-        Ok(fs::write(path, value)?)
+        fs::write(&path, value).map_err(|err| AttributeError::write_rejected(&path, err))
     } else {
         error!(
             "Cannot write attribute property. Attribute {:?} property {:?} not found",
@@ -391,30 +1535,99 @@ fn write_attribute_property(
     }
 }
 
+/// Writes the list value and restores the original one if the write fails,
+/// a partially applied list like boot order can leave the system unbootable
+fn write_list_with_rollback(path: &Path, value: &str) -> Result<(), AttributeError> {
+    write_with_rollback(path, value, write_attribute_property)
+}
+
+/// `write` is replaced in tests to simulate firmware failures
+fn write_with_rollback(
+    path: &Path,
+    value: &str,
+    mut write: impl FnMut(&Path, &str, &str) -> Result<(), AttributeError>,
+) -> Result<(), AttributeError> {
+    let original = read_attribute_property(path, PROPERTY_CURRENT_VALUE)?;
+    let Err(error) = write(path, PROPERTY_CURRENT_VALUE, value) else {
+        return Ok(());
+    };
+    let restored = match read_attribute_property(path, PROPERTY_CURRENT_VALUE) {
+        Ok(current) if current == original => true,
+        _ => {
+            warn!("Restoring original value {:?} of {:?}", original, path);
+            write(path, PROPERTY_CURRENT_VALUE, &original).is_ok()
+                && matches!(
+                    read_attribute_property(path, PROPERTY_CURRENT_VALUE),
+                    Ok(current) if current == original
+                )
+        }
+    };
+    Err(AttributeError::ListWriteFailed {
+        error: Box::new(error),
+        restored,
+    })
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EnumerationAttribute {
     pub common_attribute: CommonAttribute,
-    pub possible_values: Vec<String>,
+    raw_possible_values: Option<String>,
+    /// Split on first use, huge enumerations are rarely opened
+    #[cfg_attr(feature = "serde", serde(skip))]
+    possible_values: OnceLock<Vec<String>>,
 }
 
-impl TryFrom<PathBuf> for EnumerationAttribute {
+impl TryFrom<&AttributeProperties> for EnumerationAttribute {
     type Error = AttributeError;
 
-    fn try_from(value: PathBuf) -> Result<Self, Self::Error> {
-        let common_attribute = value.clone().try_into()?;
-        let possible_values: Vec<String> = try_read_attribute_property(&value, "possible_values")?
-            .map_or(Vec::new(), |s| {
-                s.split(POSSIBLE_VALUES_DELIMITER)
-                    .map(|s| s.to_string())
-                    .collect()
-            });
+    fn try_from(value: &AttributeProperties) -> Result<Self, Self::Error> {
+        let common_attribute = value.try_into()?;
         Ok(Self {
             common_attribute,
-            possible_values,
+            raw_possible_values: value.try_read("possible_values")?,
+            possible_values: OnceLock::new(),
         })
     }
 }
 
+impl EnumerationAttribute {
+    pub fn possible_values(&self) -> &Vec<String> {
+        self.possible_values.get_or_init(|| {
+            self.trimmed_possible_values()
+                .map_or(Vec::new(), |s| split_list(s, POSSIBLE_VALUES_DELIMITER))
+        })
+    }
+
+    /// Raw possible values without the trailing delimiters, as [split_list] reads them
+    fn trimmed_possible_values(&self) -> Option<&str> {
+        self.raw_possible_values
+            .as_deref()
+            .map(|s| s.trim_end_matches(POSSIBLE_VALUES_DELIMITER))
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Checks the value without splitting all possible values
+    pub fn is_possible_value(&self, value: &str) -> bool {
+        match self.possible_values.get() {
+            Some(possible_values) => possible_values.iter().any(|v| v == value),
+            None => self
+                .trimmed_possible_values()
+                .is_some_and(|s| s.split(POSSIBLE_VALUES_DELIMITER).any(|v| v == value)),
+        }
+    }
+
+    /// Counts the values without splitting them
+    pub fn possible_values_count(&self) -> usize {
+        self.trimmed_possible_values()
+            .map_or(0, |s| s.matches(POSSIBLE_VALUES_DELIMITER).count() + 1)
+    }
+
+    pub fn has_possible_values(&self) -> bool {
+        self.trimmed_possible_values().is_some()
+    }
+}
+
 impl ReadableAttribute for EnumerationAttribute {
     type Value = String;
 
@@ -442,27 +1655,98 @@ impl WriteableAttribute for EnumerationAttribute {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct OrderedListAttribute {
     pub common_attribute: CommonAttribute<Vec<String>>,
     pub elements: Vec<String>,
+    pub value_delimiter: String,
+    pub elements_delimiter: String,
+    pub index_prefix: Option<IndexPrefix>,
+}
+
+/// Numeric prefix embedded into each entry of HP ordered lists, like "1:Windows Boot Manager"
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct IndexPrefix {
+    pub separator: String,
+    pub first_index: usize,
 }
 
-impl TryFrom<PathBuf> for OrderedListAttribute {
+impl TryFrom<&AttributeProperties> for OrderedListAttribute {
     type Error = AttributeError;
 
-    fn try_from(value: PathBuf) -> Result<Self, Self::Error> {
-        let common_attribute = value.clone().try_into()?;
-        let elements: Vec<String> = try_read_attribute_property(&value, "elements")?
-            .or(try_read_attribute_property(&value, "possible_values")?)
-            .map_or(Vec::new(), |s| {
-                s.split(POSSIBLE_VALUES_DELIMITER)
-                    .map(|s| s.to_string())
-                    .collect()
-            });
-        Ok(Self {
+    fn try_from(value: &AttributeProperties) -> Result<Self, Self::Error> {
+        let common_attribute: CommonAttribute<Vec<String>> = value.try_into()?;
+        let current_value = value.try_read(PROPERTY_CURRENT_VALUE)?;
+        let elements = value
+            .try_read(PROPERTY_ELEMENTS)?
+            .or(value.try_read("possible_values")?);
+        // Drivers can use different delimiters for the value and for the elements
+        let value_delimiter = detect_delimiter(
+            &[current_value.as_deref(), elements.as_deref()],
+            ORDERED_LIST_DELIMITERS,
+        );
+        let elements_delimiter = detect_delimiter(
+            &[elements.as_deref(), current_value.as_deref()],
+            ORDERED_LIST_DELIMITERS,
+        );
+        let index_prefix = match (common_attribute.vendor(), &current_value) {
+            (Vendor::Hp, Some(current_value)) => {
+                detect_index_prefix(current_value, value_delimiter)
+            }
+            _ => None,
+        };
+        let mut attribute = Self {
             common_attribute,
-            elements,
-        })
+            elements: Vec::new(),
+            value_delimiter: value_delimiter.to_string(),
+            elements_delimiter: elements_delimiter.to_string(),
+            index_prefix,
+        };
+        if let Some(elements) = elements {
+            attribute.elements = attribute.split_entries(&elements, elements_delimiter);
+        }
+        // The default value uses the detected delimiter and index prefix as the current one
+        attribute.common_attribute.default_value = value
+            .try_read(PROPERTY_DEFAULT_VALUE)?
+            .map(|default_value| attribute.parse_list(&default_value));
+        Ok(attribute)
+    }
+}
+
+impl OrderedListAttribute {
+    pub fn parse_list(&self, value: &str) -> Vec<String> {
+        self.split_entries(value, &self.value_delimiter)
+    }
+
+    fn split_entries(&self, value: &str, delimiter: &str) -> Vec<String> {
+        split_list(value, delimiter)
+            .into_iter()
+            .map(|entry| match &self.index_prefix {
+                Some(_) => split_index_prefix(&entry)
+                    .map_or(entry.clone(), |(_, _, entry)| entry.to_string()),
+                None => entry,
+            })
+            .collect()
+    }
+
+    pub fn format_list(&self, value: &[String]) -> String {
+        match &self.index_prefix {
+            Some(prefix) => value
+                .iter()
+                .enumerate()
+                .map(|(index, entry)| {
+                    format!(
+                        "{}{}{}",
+                        prefix.first_index + index,
+                        prefix.separator,
+                        entry
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join(&self.value_delimiter),
+            None => value.join(&self.value_delimiter),
+        }
     }
 }
 
@@ -474,15 +1758,11 @@ impl ReadableAttribute for OrderedListAttribute {
     }
 
     fn current_value(&self) -> Result<Vec<String>, AttributeError> {
-        let value = self.common_attribute.current_value_cache_or(|| {
+        self.common_attribute.current_value_cache_or(|| {
             let string =
                 read_attribute_property(&self.common_attribute.path, PROPERTY_CURRENT_VALUE)?;
-            Ok(string
-                .split(POSSIBLE_VALUES_DELIMITER)
-                .map(|s| s.to_string())
-                .collect())
-        });
-        value
+            Ok(self.parse_list(&string))
+        })
     }
 }
 
@@ -491,54 +1771,176 @@ impl WriteableAttribute for OrderedListAttribute {
         &self,
         value: &<Self as ReadableAttribute>::Value,
     ) -> Result<(), AttributeError> {
-        let result = write_attribute_property(
-            &self.common_attribute.path,
-            PROPERTY_CURRENT_VALUE,
-            &value.join(POSSIBLE_VALUES_DELIMITER),
-        );
+        let result =
+            write_list_with_rollback(&self.common_attribute.path, &self.format_list(value));
         self.common_attribute.clear_current_value_cache();
         result
     }
 }
 
+fn detect_index_prefix(value: &str, delimiter: &str) -> Option<IndexPrefix> {
+    let prefixes: Option<Vec<(usize, &str, &str)>> = value
+        .trim_end_matches(delimiter)
+        .split(delimiter)
+        .map(split_index_prefix)
+        .collect();
+    let prefixes = prefixes?;
+    let (_, separator, _) = prefixes.first()?;
+    Some(IndexPrefix {
+        separator: separator.to_string(),
+        first_index: prefixes.iter().map(|(index, _, _)| *index).min()?,
+    })
+}
+
+fn split_index_prefix(entry: &str) -> Option<(usize, &str, &str)> {
+    let digits = entry.len() - entry.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let rest = &entry[digits..];
+    let stripped = rest.trim_start_matches(INDEX_PREFIX_SEPARATORS);
+    if digits == 0 || stripped.len() == rest.len() || stripped.is_empty() {
+        None
+    } else {
+        let separator = &rest[..rest.len() - stripped.len()];
+        Some((entry[..digits].parse().ok()?, separator, stripped))
+    }
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EnumerationListAttribute {
     pub common_attribute: CommonAttribute<Vec<String>>,
     pub possible_values: Vec<String>,
+    pub value_delimiter: String,
+    pub possible_values_delimiter: String,
+    pub empty_list_policy: EmptyListPolicy,
 }
 
-impl TryFrom<PathBuf> for EnumerationListAttribute {
+impl TryFrom<&AttributeProperties> for EnumerationListAttribute {
     type Error = AttributeError;
 
-    fn try_from(value: PathBuf) -> Result<Self, Self::Error> {
-        let common_attribute = CommonAttribute {
-            name: attribute_name(&value),
-            default_value: try_read_attribute_property(&value, PROPERTY_DEFAULT_VALUE)?.map(|s| {
-                s.split(ENUMERATION_VALUES_DELIMITER)
-                    .map(|s| s.to_string())
-                    .collect()
-            }),
-            display_name: try_read_attribute_property(&value, PROPERTY_DISPLAY_NAME)?,
-            display_name_language_code: try_read_attribute_property(
-                &value,
-                "display_name_language_code",
-            )?,
-            path: value.clone(),
-            current_value_cache: Arc::new(Mutex::default()),
-        };
-        let possible_values: Vec<String> = try_read_attribute_property(&value, "possible_values")?
-            .map_or(Vec::new(), |s| {
-                s.split(POSSIBLE_VALUES_DELIMITER)
-                    .map(|s| s.to_string())
-                    .collect()
-            });
-        Ok(Self {
+    fn try_from(value: &AttributeProperties) -> Result<Self, Self::Error> {
+        let current_value = value.try_read(PROPERTY_CURRENT_VALUE)?;
+        let default_value = value.try_read(PROPERTY_DEFAULT_VALUE)?;
+        let possible_values = value
+            .try_read("possible_values")?
+            .or(value.try_read(PROPERTY_ELEMENTS)?);
+        let value_delimiter = detect_delimiter(
+            &[current_value.as_deref(), default_value.as_deref()],
+            ENUMERATION_LIST_VALUES_DELIMITERS,
+        );
+        let possible_values_delimiter = detect_delimiter(
+            &[possible_values.as_deref()],
+            ENUMERATION_LIST_POSSIBLE_VALUES_DELIMITERS,
+        );
+        let possible_values =
+            possible_values.map_or(Vec::new(), |s| split_list(&s, possible_values_delimiter));
+        let common_attribute = CommonAttribute::with_default_value(value, None)?;
+        let empty_list_policy = EmptyListPolicy::detect(
+            &common_attribute.name,
+            common_attribute.vendor(),
+            &[current_value.as_deref(), default_value.as_deref()],
+            &possible_values,
+        );
+        let mut attribute = Self {
             common_attribute,
             possible_values,
+            value_delimiter: value_delimiter.to_string(),
+            possible_values_delimiter: possible_values_delimiter.to_string(),
+            empty_list_policy,
+        };
+        attribute.common_attribute.default_value =
+            default_value.map(|default_value| attribute.parse_list(&default_value));
+        Ok(attribute)
+    }
+}
+
+/// What is written to clear an enumeration list, firmware differs in what it accepts
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum EmptyListPolicy {
+    EmptyString,
+    Sentinel(&'static str),
+    Refuse,
+}
+
+/// Only the known sentinels can be restored as `&'static str`
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for EmptyListPolicy {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        enum EmptyListPolicy {
+            EmptyString,
+            Sentinel(String),
+            Refuse,
+        }
+        Ok(match EmptyListPolicy::deserialize(deserializer)? {
+            EmptyListPolicy::EmptyString => Self::EmptyString,
+            EmptyListPolicy::Sentinel(sentinel) => Self::Sentinel(
+                EMPTY_LIST_SENTINELS
+                    .iter()
+                    .find(|known| **known == sentinel)
+                    .ok_or_else(|| {
+                        serde::de::Error::custom(format!(
+                            "unknown empty list sentinel {:?}",
+                            sentinel
+                        ))
+                    })?,
+            ),
+            EmptyListPolicy::Refuse => Self::Refuse,
         })
     }
 }
 
+impl EmptyListPolicy {
+    fn detect(
+        name: &str,
+        vendor: Vendor,
+        raw_values: &[Option<&str>],
+        possible_values: &[String],
+    ) -> Self {
+        if let Some((_, _, policy)) = EMPTY_LIST_POLICIES
+            .iter()
+            .find(|(v, n, _)| *v == vendor && *n == name)
+        {
+            *policy
+        } else if raw_values.iter().flatten().any(|raw| raw.is_empty()) {
+            Self::EmptyString
+        } else if let Some(sentinel) = EMPTY_LIST_SENTINELS
+            .iter()
+            .find(|sentinel| possible_values.iter().any(|value| value == *sentinel))
+        {
+            Self::Sentinel(sentinel)
+        } else {
+            Self::EmptyString
+        }
+    }
+}
+
+impl EnumerationListAttribute {
+    pub fn parse_list(&self, value: &str) -> Vec<String> {
+        match self.empty_list_policy {
+            _ if value.is_empty() => Vec::new(),
+            EmptyListPolicy::Sentinel(sentinel) if value == sentinel => Vec::new(),
+            _ => split_list(value, &self.value_delimiter),
+        }
+    }
+
+    pub fn format_list(&self, value: &[String]) -> String {
+        match self.empty_list_policy {
+            EmptyListPolicy::Sentinel(sentinel) if value.is_empty() => sentinel.to_string(),
+            _ => value.join(&self.value_delimiter),
+        }
+    }
+}
+
+/// Trailing delimiters produce no phantom empty members, empty members in the middle are kept
+fn split_list(value: &str, delimiter: &str) -> Vec<String> {
+    let value = value.trim_end_matches(delimiter);
+    if value.is_empty() {
+        return Vec::new();
+    }
+    value.split(delimiter).map(|s| s.to_string()).collect()
+}
+
 impl ReadableAttribute for EnumerationListAttribute {
     type Value = Vec<String>;
 
@@ -550,10 +1952,7 @@ impl ReadableAttribute for EnumerationListAttribute {
         let value = self.common_attribute.current_value_cache_or(|| {
             let string =
                 read_attribute_property(&self.common_attribute.path, PROPERTY_CURRENT_VALUE)?;
-            Ok(string
-                .split(ENUMERATION_VALUES_DELIMITER)
-                .map(|s| s.to_string())
-                .collect())
+            Ok(self.parse_list(&string))
         });
         value
     }
@@ -564,39 +1963,46 @@ impl WriteableAttribute for EnumerationListAttribute {
         &self,
         value: &<Self as ReadableAttribute>::Value,
     ) -> Result<(), AttributeError> {
-        let result = write_attribute_property(
-            &self.common_attribute.path,
-            PROPERTY_CURRENT_VALUE,
-            &value.join(ENUMERATION_VALUES_DELIMITER),
-        );
+        if value.is_empty() && self.empty_list_policy == EmptyListPolicy::Refuse {
+            return Err(AttributeError::InvalidValue(format!(
+                "Attribute {:?} cannot be cleared",
+                self.common_attribute.display_name()
+            )));
+        }
+        let result =
+            write_list_with_rollback(&self.common_attribute.path, &self.format_list(value));
         self.common_attribute.clear_current_value_cache();
         result
     }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct IntegerAttribute {
-    pub common_attribute: CommonAttribute<i32>,
-    pub min_value: i32,
-    pub max_value: i32,
-    pub scalar_increment: i32,
+    pub common_attribute: CommonAttribute<i64>,
+    pub min_value: i64,
+    pub max_value: i64,
+    pub scalar_increment: i64,
 }
 
-impl TryFrom<PathBuf> for IntegerAttribute {
+impl TryFrom<&AttributeProperties> for IntegerAttribute {
     type Error = AttributeError;
 
-    fn try_from(value: PathBuf) -> Result<Self, Self::Error> {
-        let common_attribute = value.clone().try_into()?;
-        let min_value = try_read_attribute_property(&value, "min_value")?
-            .map(|s| i32::from_str(s.as_str()))
+    fn try_from(value: &AttributeProperties) -> Result<Self, Self::Error> {
+        let common_attribute = value.try_into()?;
+        let min_value = value
+            .try_read("min_value")?
+            .map(|s| i64::from_str(s.as_str()))
             .transpose()?
             .unwrap_or(DEFAULT_INTEGER_MIN_VALUE);
-        let max_value = try_read_attribute_property(&value, "max_value")?
-            .map(|s| i32::from_str(s.as_str()))
+        let max_value = value
+            .try_read("max_value")?
+            .map(|s| i64::from_str(s.as_str()))
             .transpose()?
             .unwrap_or(DEFAULT_INTEGER_MAX_VALUE);
-        let scalar_increment = try_read_attribute_property(&value, "scalar_increment")?
-            .map(|s| i32::from_str(s.as_str()))
+        let scalar_increment = value
+            .try_read("scalar_increment")?
+            .map(|s| i64::from_str(s.as_str()))
             .transpose()?
             .unwrap_or(DEFAULT_INTEGER_SCALAR_INCREMENT);
         Ok(Self {
@@ -608,18 +2014,38 @@ impl TryFrom<PathBuf> for IntegerAttribute {
     }
 }
 
+impl IntegerAttribute {
+    /// Nearest value inside of the range aligned to the scalar increment
+    pub fn round_to_increment(&self, value: i64) -> i64 {
+        let value = value.clamp(self.min_value, self.max_value);
+        if self.scalar_increment <= 1 {
+            return value;
+        }
+        let (min, max, step) = (
+            self.min_value as i128,
+            self.max_value as i128,
+            self.scalar_increment as i128,
+        );
+        let mut rounded = min + (value as i128 - min + step / 2) / step * step;
+        if rounded > max {
+            rounded -= step;
+        }
+        rounded as i64
+    }
+}
+
 impl ReadableAttribute for IntegerAttribute {
-    type Value = i32;
+    type Value = i64;
 
     fn common_attribute(&self) -> &CommonAttribute<Self::Value> {
         &self.common_attribute
     }
 
-    fn current_value(&self) -> Result<i32, AttributeError> {
+    fn current_value(&self) -> Result<i64, AttributeError> {
         self.common_attribute.current_value_cache_or(|| {
             let string =
                 read_attribute_property(&self.common_attribute.path, PROPERTY_CURRENT_VALUE)?;
-            Ok(i32::from_str(&string)?)
+            Ok(i64::from_str(&string)?)
         })
     }
 }
@@ -640,6 +2066,7 @@ impl WriteableAttribute for IntegerAttribute {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct StringAttribute {
     pub common_attribute: CommonAttribute,
     pub max_length: usize,
@@ -647,20 +2074,22 @@ pub struct StringAttribute {
     pub hint: Option<String>,
 }
 
-impl TryFrom<PathBuf> for StringAttribute {
+impl TryFrom<&AttributeProperties> for StringAttribute {
     type Error = AttributeError;
 
-    fn try_from(value: PathBuf) -> Result<Self, Self::Error> {
-        let common_attribute = value.clone().try_into()?;
-        let min_length = try_read_attribute_property(&value, "min_length")?
+    fn try_from(value: &AttributeProperties) -> Result<Self, Self::Error> {
+        let common_attribute = value.try_into()?;
+        let min_length = value
+            .try_read("min_length")?
             .map(|s| usize::from_str(s.as_str()))
             .transpose()?
             .unwrap_or(DEFAULT_MIN_STRING_LENGTH);
-        let max_length = try_read_attribute_property(&value, "max_length")?
+        let max_length = value
+            .try_read("max_length")?
             .map(|s| usize::from_str(s.as_str()))
             .transpose()?
             .unwrap_or(DEFAULT_MAX_STRING_LENGTH);
-        let hint = try_read_attribute_property(&value, "possible_values")?;
+        let hint = value.try_read("possible_values")?;
         Ok(Self {
             common_attribute,
             min_length,
@@ -697,6 +2126,156 @@ impl WriteableAttribute for StringAttribute {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MapAttribute {
+    pub common_attribute: CommonAttribute<Vec<(String, String)>>,
+    /// The firmware terminates the last entry with the delimiter too
+    pub trailing_delimiter: bool,
+}
+
+impl TryFrom<&AttributeProperties> for CommonAttribute<Vec<(String, String)>> {
+    type Error = AttributeError;
+
+    fn try_from(properties: &AttributeProperties) -> Result<Self, Self::Error> {
+        let default_value = properties
+            .try_read(PROPERTY_DEFAULT_VALUE)?
+            .map(|s| parse_map(&s));
+        Self::with_default_value(properties, default_value)
+    }
+}
+
+impl TryFrom<&AttributeProperties> for MapAttribute {
+    type Error = AttributeError;
+
+    fn try_from(value: &AttributeProperties) -> Result<Self, Self::Error> {
+        let common_attribute = value.try_into()?;
+        let trailing_delimiter = value
+            .try_read(PROPERTY_CURRENT_VALUE)?
+            .is_some_and(|s| s.ends_with(MAP_ENTRIES_DELIMITER));
+        Ok(Self {
+            common_attribute,
+            trailing_delimiter,
+        })
+    }
+}
+
+impl MapAttribute {
+    pub fn format_map(&self, entries: &[(String, String)]) -> String {
+        format_map(entries, self.trailing_delimiter)
+    }
+}
+
+/// At least two `key=value` entries, a single `=` is too common in plain strings
+fn is_map(value: &str) -> bool {
+    let entries: Vec<&str> = value
+        .split(MAP_ENTRIES_DELIMITER)
+        .filter(|entry| !entry.is_empty())
+        .collect();
+    entries.len() > 1
+        && entries.iter().all(|entry| {
+            entry
+                .split_once(MAP_KEY_VALUE_DELIMITER)
+                .is_some_and(|(key, _)| !key.trim().is_empty())
+        })
+}
+
+fn parse_map(value: &str) -> Vec<(String, String)> {
+    value
+        .split(MAP_ENTRIES_DELIMITER)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| match entry.split_once(MAP_KEY_VALUE_DELIMITER) {
+            Some((key, value)) => (key.to_string(), value.to_string()),
+            None => (entry.to_string(), String::new()),
+        })
+        .collect()
+}
+
+fn format_map(entries: &[(String, String)], trailing_delimiter: bool) -> String {
+    let mut value = entries
+        .iter()
+        .map(|(key, value)| format!("{}{}{}", key, MAP_KEY_VALUE_DELIMITER, value))
+        .collect::<Vec<_>>()
+        .join(MAP_ENTRIES_DELIMITER);
+    if trailing_delimiter && !entries.is_empty() {
+        value.push_str(MAP_ENTRIES_DELIMITER);
+    }
+    value
+}
+
+impl ReadableAttribute for MapAttribute {
+    type Value = Vec<(String, String)>;
+
+    fn common_attribute(&self) -> &CommonAttribute<Self::Value> {
+        &self.common_attribute
+    }
+
+    fn current_value(&self) -> Result<Self::Value, AttributeError> {
+        self.common_attribute.current_value_cache_or(|| {
+            read_attribute_property(&self.common_attribute.path, PROPERTY_CURRENT_VALUE)
+                .map(|value| parse_map(&value))
+        })
+    }
+}
+
+impl WriteableAttribute for MapAttribute {
+    fn write_current_value(
+        &self,
+        value: &<Self as ReadableAttribute>::Value,
+    ) -> Result<(), AttributeError> {
+        let result = write_attribute_property(
+            &self.common_attribute.path,
+            PROPERTY_CURRENT_VALUE,
+            &self.format_map(value),
+        );
+        self.common_attribute.clear_current_value_cache();
+        result
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RawAttribute {
+    pub common_attribute: CommonAttribute,
+}
+
+impl TryFrom<&AttributeProperties> for RawAttribute {
+    type Error = AttributeError;
+
+    fn try_from(value: &AttributeProperties) -> Result<Self, Self::Error> {
+        Ok(Self {
+            common_attribute: value.try_into()?,
+        })
+    }
+}
+
+impl ReadableAttribute for RawAttribute {
+    type Value = String;
+
+    fn common_attribute(&self) -> &CommonAttribute<Self::Value> {
+        &self.common_attribute
+    }
+
+    fn current_value(&self) -> Result<String, AttributeError> {
+        self.common_attribute.current_value_cache_or(|| {
+            read_attribute_property(&self.common_attribute.path, PROPERTY_CURRENT_VALUE)
+        })
+    }
+}
+
+impl WriteableAttribute for RawAttribute {
+    fn write_current_value(
+        &self,
+        value: &<Self as ReadableAttribute>::Value,
+    ) -> Result<(), AttributeError> {
+        let result =
+            write_attribute_property(&self.common_attribute.path, PROPERTY_CURRENT_VALUE, value);
+        self.common_attribute.clear_current_value_cache();
+        result
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Authentication {
     pub path: PathBuf,
     pub login: String,
@@ -705,14 +2284,16 @@ pub struct Authentication {
     pub mechanism: Mechanism,
     pub max_password_length: usize,
     pub min_password_length: usize,
+    /// Failed logins allowed before the firmware locks, `None` when the firmware doesn't tell
+    pub max_password_retries: Option<u32>,
 }
 
 impl TryFrom<PathBuf> for Authentication {
     type Error = AttributeError;
 
     fn try_from(path: PathBuf) -> Result<Self, Self::Error> {
-        let login = path.file_name().unwrap().to_str().unwrap().to_string();
-        let is_enabled = read_attribute_property(&path, "is_enabled")?.eq("1");
+        let login = attribute_name(&path)?;
+        let is_enabled = read_attribute_property(&path, PROPERTY_IS_ENABLED)?.eq("1");
         let role = Role::from_str(read_attribute_property(&path, "role")?.as_str())?;
         let mechanism = Mechanism::from_str(read_attribute_property(&path, "mechanism")?.as_str())?;
         let min_password_length = try_read_attribute_property(&path, "min_password_length")?
@@ -723,6 +2304,9 @@ impl TryFrom<PathBuf> for Authentication {
             .map(|s| usize::from_str(s.as_str()))
             .transpose()?
             .unwrap_or(DEFAULT_MAX_PASSWORD_LENGTH);
+        let max_password_retries = try_read_attribute_property(&path, PROPERTY_PASSWORD_MAX_RETRY)?
+            .map(|s| u32::from_str(s.trim()))
+            .transpose()?;
         Ok(Self {
             path,
             login,
@@ -731,17 +2315,71 @@ impl TryFrom<PathBuf> for Authentication {
             mechanism,
             max_password_length,
             min_password_length,
+            max_password_retries,
         })
     }
 }
 
 impl Authentication {
+    pub fn is_password_length_valid(&self, password: &str) -> bool {
+        (self.min_password_length..=self.max_password_length).contains(&password.chars().count())
+    }
+
+    pub fn current_password_path(&self) -> PathBuf {
+        self.path.join(PROPERTY_CURRENT_PASSWORD)
+    }
+
     pub fn authenticate_with_password(&self, password: &str) -> Result<(), AttributeError> {
         write_attribute_property(&self.path, PROPERTY_CURRENT_PASSWORD, password)
     }
+
+    /// Authenticates with the current password and sets the new one, the new password length
+    /// is validated before anything is written
+    pub fn change_password(&self, current: &str, new: &str) -> Result<(), AttributeError> {
+        if !self.is_password_length_valid(new) {
+            return Err(AttributeError::InvalidValue(format!(
+                "New password length must be {}..={}",
+                self.min_password_length, self.max_password_length
+            )));
+        }
+        self.authenticate_with_password(current).map_err(|error| {
+            AttributeError::PasswordChangeFailed {
+                error: Box::new(error),
+                current_accepted: false,
+            }
+        })?;
+        write_attribute_property(&self.path, PROPERTY_NEW_PASSWORD, new).map_err(|error| {
+            AttributeError::PasswordChangeFailed {
+                error: Box::new(error),
+                current_accepted: true,
+            }
+        })
+    }
+
+    /// Writes the signature file, signed with the private key of the installed certificate
+    pub fn authenticate_with_certificate(&self, signature: &Path) -> Result<(), AttributeError> {
+        let signature = fs::read_to_string(signature)?;
+        write_attribute_property(&self.path, PROPERTY_SIGNATURE, signature.trim())
+    }
+
+    /// Firmware can expose `is_enabled` as a read-only property
+    pub fn is_enabled_writable(&self) -> bool {
+        fs::metadata(self.path.join(PROPERTY_IS_ENABLED))
+            .is_ok_and(|metadata| metadata.mode() & 0o222 != 0)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) -> Result<(), AttributeError> {
+        let value = if enabled { "1" } else { "0" };
+        write_attribute_property(&self.path, PROPERTY_IS_ENABLED, value)
+    }
 }
 
 #[derive(Debug, EnumString, AsRefStr, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "kebab-case")
+)]
 pub enum Role {
     #[strum(serialize = "bios-admin")]
     BiosAdmin,
@@ -752,15 +2390,543 @@ pub enum Role {
     #[strum(serialize = "system")]
     System,
     #[strum(serialize = "hdd")]
+    #[cfg_attr(feature = "serde", serde(rename = "hdd"))]
     HDD, // Lenovo
     #[strum(serialize = "nvme")]
+    #[cfg_attr(feature = "serde", serde(rename = "nvme"))]
     NVMe, // Lenovo
     #[strum(serialize = "enhanced-bios-auth")]
     EnhancedBiosAuth, // HP
 }
 
 #[derive(Debug, EnumString, AsRefStr, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "kebab-case")
+)]
 pub enum Mechanism {
     #[strum(serialize = "password")]
     Password,
+    #[strum(serialize = "certificate")]
+    Certificate,
+}
+
+#[cfg(test)]
+mod test_root;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_root::{current_value, TestRoot};
+
+    #[test]
+    fn ordered_list_keeps_comma_delimiter() {
+        let root = TestRoot::new("ordered_list_keeps_comma_delimiter");
+        let path = root.attribute(
+            "UefiBoot",
+            &[
+                (PROPERTY_TYPE, TYPE_ORDERED_LIST),
+                (PROPERTY_CURRENT_VALUE, "a,b,c"),
+                (PROPERTY_ELEMENTS, "a,b,c"),
+            ],
+        );
+        let Ok(Attribute::OrderedList(attribute)) = Attribute::try_from(path.clone()) else {
+            panic!("ordered list expected");
+        };
+        assert_eq!(attribute.current_value().unwrap(), ["a", "b", "c"]);
+        attribute
+            .write_current_value(&vec!["c".into(), "a".into(), "b".into()])
+            .unwrap();
+        assert_eq!(current_value(&path), "c,a,b");
+    }
+
+    #[test]
+    fn enumeration_without_possible_values() {
+        let root = TestRoot::new("enumeration_without_possible_values");
+        let path = root.attribute(
+            "Mode",
+            &[
+                (PROPERTY_TYPE, TYPE_ENUMERATION),
+                (PROPERTY_CURRENT_VALUE, "Auto"),
+            ],
+        );
+        let attribute = Attribute::try_from(path).unwrap();
+        let Attribute::Enumeration(enumeration) = &attribute else {
+            panic!("enumeration expected");
+        };
+        assert!(!enumeration.has_possible_values());
+        assert_eq!(enumeration.possible_values_count(), 0);
+        assert!(enumeration.possible_values().is_empty());
+        assert_eq!(enumeration.current_value().unwrap(), "Auto");
+        // Nothing to check the value against
+        assert!(attribute
+            .validate_value(&AttributeValue::String("Manual".into()))
+            .is_ok());
+    }
+
+    #[test]
+    fn hp_boot_order_index_prefixes() {
+        let root = TestRoot::with_driver("hp_boot_order_index_prefixes", DRIVER_HP);
+        let path = root.attribute(
+            "UEFI Boot Order",
+            &[
+                (PROPERTY_TYPE, TYPE_ORDERED_LIST),
+                (
+                    PROPERTY_CURRENT_VALUE,
+                    "1:Windows Boot Manager,2:USB Hard Drive,3:IPV4 Network",
+                ),
+            ],
+        );
+        let Ok(Attribute::OrderedList(attribute)) = Attribute::try_from(path.clone()) else {
+            panic!("ordered list expected");
+        };
+        assert_eq!(
+            attribute.current_value().unwrap(),
+            ["Windows Boot Manager", "USB Hard Drive", "IPV4 Network"]
+        );
+        attribute
+            .write_current_value(&vec![
+                "USB Hard Drive".into(),
+                "Windows Boot Manager".into(),
+                "IPV4 Network".into(),
+            ])
+            .unwrap();
+        assert_eq!(
+            current_value(&path),
+            "1:USB Hard Drive,2:Windows Boot Manager,3:IPV4 Network"
+        );
+    }
+
+    #[test]
+    fn authentication_enabled_state_write() {
+        let root = TestRoot::new("authentication_enabled_state_write");
+        let path = root.authentication("Admin", "bios-admin", true);
+        let authentication = Authentication::try_from(path.clone()).unwrap();
+        assert!(authentication.is_enabled);
+        assert!(authentication.is_enabled_writable());
+        authentication.set_enabled(false).unwrap();
+        assert_eq!(
+            fs::read_to_string(path.join(PROPERTY_IS_ENABLED)).unwrap(),
+            "0"
+        );
+        assert!(!Authentication::try_from(path.clone()).unwrap().is_enabled);
+
+        let is_enabled = path.join(PROPERTY_IS_ENABLED);
+        let mut permissions = fs::metadata(&is_enabled).unwrap().permissions();
+        std::os::unix::fs::PermissionsExt::set_mode(&mut permissions, 0o444);
+        fs::set_permissions(&is_enabled, permissions).unwrap();
+        assert!(!authentication.is_enabled_writable());
+    }
+
+    #[test]
+    fn enumeration_list_mixed_delimiters() {
+        let root = TestRoot::new("enumeration_list_mixed_delimiters");
+        let path = root.attribute(
+            "Features",
+            &[
+                (PROPERTY_TYPE, TYPE_ENUMERATION_LIST),
+                (PROPERTY_CURRENT_VALUE, "A:B"),
+                ("possible_values", "A;B;C"),
+            ],
+        );
+        let Ok(Attribute::EnumerationList(attribute)) = Attribute::try_from(path.clone()) else {
+            panic!("enumeration list expected");
+        };
+        assert_eq!(attribute.possible_values, ["A", "B", "C"]);
+        assert_eq!(attribute.current_value().unwrap(), ["A", "B"]);
+        attribute
+            .write_current_value(&vec!["C".into(), "A".into()])
+            .unwrap();
+        assert_eq!(current_value(&path), "C:A");
+    }
+
+    #[test]
+    fn ordered_list_mixed_delimiters() {
+        let root = TestRoot::new("ordered_list_mixed_delimiters");
+        let path = root.attribute(
+            "BootOrder",
+            &[
+                (PROPERTY_TYPE, TYPE_ORDERED_LIST),
+                (PROPERTY_CURRENT_VALUE, "b;a"),
+                (PROPERTY_ELEMENTS, "a,b,c"),
+            ],
+        );
+        let Ok(Attribute::OrderedList(attribute)) = Attribute::try_from(path.clone()) else {
+            panic!("ordered list expected");
+        };
+        assert_eq!(attribute.elements, ["a", "b", "c"]);
+        assert_eq!(attribute.current_value().unwrap(), ["b", "a"]);
+        attribute
+            .write_current_value(&vec!["c".into(), "b".into(), "a".into()])
+            .unwrap();
+        assert_eq!(current_value(&path), "c;b;a");
+    }
+
+    /// Compares the single walk with reading each Attribute on demand, run with
+    /// `cargo test --release -- --ignored --nocapture bulk_read`
+    #[test]
+    #[ignore]
+    fn bulk_read_benchmark() {
+        let root = TestRoot::new("bulk_read_benchmark");
+        for index in 0..500 {
+            root.attribute(
+                &format!("Attribute{}", index),
+                &[
+                    (PROPERTY_TYPE, TYPE_ENUMERATION),
+                    (PROPERTY_CURRENT_VALUE, "Enabled"),
+                    (PROPERTY_DEFAULT_VALUE, "Disabled"),
+                    (PROPERTY_DISPLAY_NAME, "Attribute"),
+                    ("possible_values", "Disabled;Enabled"),
+                ],
+            );
+        }
+        let started = Instant::now();
+        let per_attribute: Vec<AttributeValue> = Attribute::attributes_names(&root.root)
+            .unwrap()
+            .iter()
+            .map(|name| Attribute::attribute(&root.root, name).unwrap())
+            .map(|attribute| attribute.current_value().unwrap())
+            .collect();
+        let per_attribute_elapsed = started.elapsed();
+        let started = Instant::now();
+        let bulk: Vec<AttributeValue> = read_attributes(&root.root)
+            .unwrap()
+            .into_iter()
+            .map(|(_, attribute)| attribute.unwrap().current_value().unwrap())
+            .collect();
+        let bulk_elapsed = started.elapsed();
+        assert_eq!(per_attribute, bulk);
+        println!(
+            "{} Attributes: per attribute {:?}, single walk {:?}",
+            bulk.len(),
+            per_attribute_elapsed,
+            bulk_elapsed
+        );
+    }
+
+    /// Measures what the lazy split of possible values saves at startup, run with
+    /// `cargo test --release -- --ignored --nocapture lazy_possible_values`
+    #[test]
+    #[ignore]
+    fn lazy_possible_values_benchmark() {
+        let root = TestRoot::new("lazy_possible_values_benchmark");
+        let possible_values: Vec<String> = (0..2000).map(|i| format!("Value{}", i)).collect();
+        let possible_values = possible_values.join(POSSIBLE_VALUES_DELIMITER);
+        for index in 0..200 {
+            root.attribute(
+                &format!("Attribute{}", index),
+                &[
+                    (PROPERTY_TYPE, TYPE_ENUMERATION),
+                    (PROPERTY_CURRENT_VALUE, "Value0"),
+                    ("possible_values", &possible_values),
+                ],
+            );
+        }
+        let started = Instant::now();
+        let enumerations: Vec<EnumerationAttribute> = read_attributes(&root.root)
+            .unwrap()
+            .into_iter()
+            .filter_map(|(_, attribute)| match attribute.unwrap() {
+                Attribute::Enumeration(enumeration) => Some(enumeration),
+                _ => None,
+            })
+            .collect();
+        let lazy_elapsed = started.elapsed();
+        let started = Instant::now();
+        let split_bytes: usize = enumerations
+            .iter()
+            .map(|enumeration| {
+                let possible_values = enumeration.possible_values();
+                possible_values.capacity() * std::mem::size_of::<String>()
+                    + possible_values.iter().map(String::capacity).sum::<usize>()
+            })
+            .sum();
+        let split_elapsed = started.elapsed();
+        let raw_bytes = enumerations.len() * possible_values.len();
+        println!(
+            "{} enumerations: read {:?} and {} bytes of raw values, splitting adds {:?} and {} bytes",
+            enumerations.len(),
+            lazy_elapsed,
+            raw_bytes,
+            split_elapsed,
+            split_bytes
+        );
+    }
+
+    #[test]
+    fn empty_enumeration_list_write() {
+        let root = TestRoot::new("empty_enumeration_list_write");
+        let empty = root.attribute(
+            "Features",
+            &[
+                (PROPERTY_TYPE, TYPE_ENUMERATION_LIST),
+                (PROPERTY_CURRENT_VALUE, "A:B"),
+                ("possible_values", "A;B"),
+            ],
+        );
+        let sentinel = root.attribute(
+            "Devices",
+            &[
+                (PROPERTY_TYPE, TYPE_ENUMERATION_LIST),
+                (PROPERTY_CURRENT_VALUE, "A:B"),
+                ("possible_values", "None;A;B"),
+            ],
+        );
+        for (path, expected) in [(empty, ""), (sentinel, "None")] {
+            let Ok(Attribute::EnumerationList(attribute)) = Attribute::try_from(path.clone())
+            else {
+                panic!("enumeration list expected");
+            };
+            attribute.write_current_value(&Vec::new()).unwrap();
+            assert_eq!(current_value(&path), expected);
+            assert!(attribute.current_value().unwrap().is_empty());
+        }
+
+        let lenovo = TestRoot::with_driver("empty_enumeration_list_write_lenovo", DRIVER_LENOVO);
+        let path = lenovo.attribute(
+            "BootOrder",
+            &[
+                (PROPERTY_TYPE, TYPE_ENUMERATION),
+                (PROPERTY_CURRENT_VALUE, "USB:NVMe"),
+                ("possible_values", "USB;NVMe;PXE"),
+            ],
+        );
+        let Ok(Attribute::EnumerationList(attribute)) = Attribute::try_from(path.clone()) else {
+            panic!("enumeration list expected");
+        };
+        assert_eq!(attribute.empty_list_policy, EmptyListPolicy::Refuse);
+        assert!(attribute.write_current_value(&Vec::new()).is_err());
+        assert_eq!(current_value(&path), "USB:NVMe\n");
+    }
+
+    #[test]
+    fn failed_list_write_restores_original() {
+        let root = TestRoot::new("failed_list_write_restores_original");
+        let path = root.attribute(
+            "BootOrder",
+            &[
+                (PROPERTY_TYPE, TYPE_ORDERED_LIST),
+                (PROPERTY_CURRENT_VALUE, "a;b;c"),
+            ],
+        );
+        // The firmware applies a part of the new order before rejecting it
+        let mut writes = 0;
+        let result = write_with_rollback(&path, "c;b;a", |path, property, value| {
+            writes += 1;
+            if writes == 1 {
+                write_attribute_property(path, property, "c;a")?;
+                Err(AttributeError::InvalidValue("rejected".into()))
+            } else {
+                write_attribute_property(path, property, value)
+            }
+        });
+        assert!(matches!(
+            result,
+            Err(AttributeError::ListWriteFailed { restored: true, .. })
+        ));
+        assert_eq!(current_value(&path), "a;b;c");
+
+        let result = write_with_rollback(&path, "c;b;a", |path, property, _| {
+            write_attribute_property(path, property, "c")?;
+            Err(AttributeError::InvalidValue("rejected".into()))
+        });
+        assert!(matches!(
+            result,
+            Err(AttributeError::ListWriteFailed {
+                restored: false,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn write_once_properties() {
+        let root = TestRoot::new("write_once_properties");
+        let attribute = |name, properties: &[(&str, &str)]| {
+            let mut properties = properties.to_vec();
+            properties.push((PROPERTY_TYPE, TYPE_STRING));
+            properties.push((PROPERTY_CURRENT_VALUE, "ABC"));
+            properties.push((PROPERTY_DEFAULT_VALUE, ""));
+            Attribute::try_from(root.attribute(name, &properties)).unwrap()
+        };
+        let write_once = attribute("WriteOnce", &[(PROPERTY_WRITE_ONCE, "1")]);
+        assert!(write_once.write_once());
+        assert!(write_once.is_programmed());
+        let otp = attribute("Otp", &[(PROPERTY_OTP, "1")]);
+        assert!(otp.write_once());
+        let disabled = attribute("Disabled", &[(PROPERTY_WRITE_ONCE, "0")]);
+        assert!(!disabled.write_once());
+        assert!(!disabled.is_programmed());
+        assert!(!attribute("Plain", &[]).write_once());
+    }
+
+    #[test]
+    fn map_round_trip() {
+        let root = TestRoot::new("map_round_trip");
+        let entries = |entries: &[(&str, &str)]| -> Vec<(String, String)> {
+            entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect()
+        };
+        for (name, value) in [("Plain", "a=1;b=2"), ("Trailing", "a=1;b=2;")] {
+            let path = root.attribute(
+                name,
+                &[
+                    (PROPERTY_TYPE, TYPE_STRING),
+                    (PROPERTY_CURRENT_VALUE, value),
+                ],
+            );
+            let attribute = Attribute::try_from(path.clone()).unwrap();
+            assert_eq!(attribute.attribute_type(), TYPE_MAP);
+            assert_eq!(
+                attribute.current_value().unwrap(),
+                AttributeValue::Map(entries(&[("a", "1"), ("b", "2")]))
+            );
+            attribute
+                .write_value(&AttributeValue::Map(entries(&[("a", "1"), ("b", "2")])))
+                .unwrap();
+            assert_eq!(current_value(&path), value);
+            attribute
+                .write_value(&AttributeValue::Map(Vec::new()))
+                .unwrap();
+            assert_eq!(current_value(&path), "");
+            assert_eq!(
+                attribute.current_value().unwrap(),
+                AttributeValue::Map(Vec::new())
+            );
+        }
+
+        #[cfg(feature = "serde")]
+        for entries in [entries(&[("b", "2"), ("a", "1")]), Vec::new()] {
+            let json = serde_json::to_string(&AttributeValue::Map(entries.clone())).unwrap();
+            assert!(json.starts_with('{'));
+            let value: AttributeValue = serde_json::from_str(&json).unwrap();
+            assert_eq!(value, AttributeValue::Map(entries));
+        }
+    }
+
+    #[test]
+    fn root_without_authentication() {
+        let root = TestRoot::new("root_without_authentication");
+        root.attribute(
+            "AssetTag",
+            &[
+                (PROPERTY_TYPE, TYPE_STRING),
+                (PROPERTY_CURRENT_VALUE, "ABC"),
+            ],
+        );
+        assert!(is_firmware_attributes_root(&root.root));
+        assert!(!has_authentications(&root.root));
+        assert!(Attribute::authentications_names(&root.root)
+            .unwrap()
+            .is_empty());
+        assert_eq!(read_attributes(&root.root).unwrap().len(), 1);
+        assert!(matches!(
+            Attribute::authentications_names(&root.directory),
+            Err(AttributeError::InvalidRoot(_))
+        ));
+    }
+
+    #[test]
+    fn list_trailing_delimiter() {
+        assert_eq!(split_list("a;b;c;", ";"), ["a", "b", "c"]);
+        assert_eq!(split_list("a;b;c;;", ";"), ["a", "b", "c"]);
+        assert_eq!(split_list("a;;c", ";"), ["a", "", "c"]);
+        assert!(split_list(";", ";").is_empty());
+        assert!(split_list("", ";").is_empty());
+
+        let root = TestRoot::new("list_trailing_delimiter");
+        let path = root.attribute(
+            "Mode",
+            &[
+                (PROPERTY_TYPE, TYPE_ENUMERATION),
+                (PROPERTY_CURRENT_VALUE, "a"),
+                ("possible_values", "a;b;"),
+            ],
+        );
+        let Attribute::Enumeration(attribute) = Attribute::try_from(path).unwrap() else {
+            panic!("enumeration expected");
+        };
+        assert_eq!(attribute.possible_values_count(), 2);
+        // Checked before and after the values are split
+        assert!(!attribute.is_possible_value(""));
+        assert_eq!(attribute.possible_values(), &["a", "b"]);
+        assert!(!attribute.is_possible_value(""));
+    }
+
+    #[test]
+    fn increment_aligned_to_min_value() {
+        let root = TestRoot::new("increment_aligned_to_min_value");
+        let path = root.attribute(
+            "WakeTime",
+            &[
+                (PROPERTY_TYPE, TYPE_INTEGER),
+                (PROPERTY_CURRENT_VALUE, "7"),
+                ("min_value", "2"),
+                ("max_value", "30"),
+                ("scalar_increment", "5"),
+            ],
+        );
+        let Attribute::Integer(attribute) = Attribute::try_from(path).unwrap() else {
+            panic!("Integer Attribute expected");
+        };
+        let rounded: Vec<i64> = [0, 4, 5, 9, 10, 27, 30, 100]
+            .into_iter()
+            .map(|value| attribute.round_to_increment(value))
+            .collect();
+        assert_eq!(rounded, [2, 2, 7, 7, 12, 27, 27, 27]);
+    }
+
+    #[test]
+    fn integer_above_i32() {
+        let root = TestRoot::new("integer_above_i32");
+        let path = root.attribute(
+            "MemorySize",
+            &[
+                (PROPERTY_TYPE, TYPE_INTEGER),
+                (PROPERTY_CURRENT_VALUE, "5000000000"),
+                ("min_value", "0"),
+                ("max_value", "8000000000"),
+            ],
+        );
+        let attribute = Attribute::try_from(path.clone()).unwrap();
+        assert_eq!(
+            attribute.current_value().unwrap(),
+            AttributeValue::Integer(5_000_000_000)
+        );
+        let value = attribute.parse_value("4294967296").unwrap();
+        assert_eq!(value, AttributeValue::Integer(4_294_967_296));
+        attribute.validate_value(&value).unwrap();
+        attribute.write_value(&value).unwrap();
+        assert_eq!(current_value(&path), "4294967296");
+
+        // Without the maximum the values are assumed to fit in 32 bits
+        let path = root.attribute(
+            "Unbounded",
+            &[(PROPERTY_TYPE, TYPE_INTEGER), (PROPERTY_CURRENT_VALUE, "0")],
+        );
+        let attribute = Attribute::try_from(path).unwrap();
+        assert!(attribute.validate_value(&value).is_err());
+
+        // The distance to the minimum does not fit in i64
+        let path = root.attribute(
+            "Offset",
+            &[
+                (PROPERTY_TYPE, TYPE_INTEGER),
+                (PROPERTY_CURRENT_VALUE, "0"),
+                ("min_value", &i64::MIN.to_string()),
+                ("max_value", &i64::MAX.to_string()),
+                ("scalar_increment", "2"),
+            ],
+        );
+        let attribute = Attribute::try_from(path).unwrap();
+        attribute
+            .validate_value(&AttributeValue::Integer(i64::MAX - 1))
+            .unwrap();
+        assert!(attribute
+            .validate_value(&AttributeValue::Integer(i64::MAX))
+            .is_err());
+    }
 }
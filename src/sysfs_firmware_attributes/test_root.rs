@@ -0,0 +1,65 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Fake Firmware Attributes roots for the tests of the library and the application
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Fake Firmware Attributes root in the temporary directory, removed on drop
+/// so failed assertions don't leave it behind
+pub struct TestRoot {
+    pub directory: PathBuf,
+    pub root: PathBuf,
+}
+
+impl TestRoot {
+    pub fn new(test: &str) -> Self {
+        Self::with_driver(test, "test")
+    }
+
+    /// Vendor specific behaviour is detected from the name of the root
+    pub fn with_driver(test: &str, driver: &str) -> Self {
+        let directory =
+            std::env::temp_dir().join(format!("fw-attr-editor-{}-{}", std::process::id(), test));
+        let _ = fs::remove_dir_all(&directory);
+        let root = directory.join(driver);
+        fs::create_dir_all(root.join("attributes")).unwrap();
+        Self { directory, root }
+    }
+
+    /// Property values get the trailing newline of sysfs
+    pub fn attribute(&self, name: &str, properties: &[(&str, &str)]) -> PathBuf {
+        let path = self.root.join("attributes").join(name);
+        fs::create_dir_all(&path).unwrap();
+        for (property, value) in properties {
+            fs::write(path.join(property), format!("{}\n", value)).unwrap();
+        }
+        path
+    }
+
+    pub fn authentication(&self, login: &str, role: &str, is_enabled: bool) -> PathBuf {
+        let path = self.root.join("authentication").join(login);
+        fs::create_dir_all(&path).unwrap();
+        let is_enabled = if is_enabled { "1" } else { "0" };
+        for (property, value) in [
+            ("is_enabled", is_enabled),
+            ("role", role),
+            ("mechanism", "password"),
+            ("current_password", ""),
+        ] {
+            fs::write(path.join(property), format!("{}\n", value)).unwrap();
+        }
+        path
+    }
+}
+
+impl Drop for TestRoot {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.directory);
+    }
+}
+
+/// Exact content of the current value file of the Attribute
+pub fn current_value(path: &Path) -> String {
+    fs::read_to_string(path.join("current_value")).unwrap()
+}